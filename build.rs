@@ -116,17 +116,17 @@ fn main() {
     }
 
     println!("cargo:rerun-if-env-changed=OPENCC_DYLIB_STDCPP");
-    if let Ok(kind) = env::var("OPENCC_DYLIB_STDCPP") {
-        if kind != "0" {
-            println!("cargo:rustc-link-lib=dylib=stdc++");
-        }
+    if let Ok(kind) = env::var("OPENCC_DYLIB_STDCPP")
+        && kind != "0"
+    {
+        println!("cargo:rustc-link-lib=dylib=stdc++");
     }
 
     println!("cargo:rerun-if-env-changed=OPENCC_STATIC_STDCPP");
-    if let Ok(kind) = env::var("OPENCC_STATIC_STDCPP") {
-        if kind != "0" {
-            println!("cargo:rustc-link-lib=static=stdc++");
-        }
+    if let Ok(kind) = env::var("OPENCC_STATIC_STDCPP")
+        && kind != "0"
+    {
+        println!("cargo:rustc-link-lib=static=stdc++");
     }
 }
 
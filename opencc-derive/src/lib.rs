@@ -0,0 +1,143 @@
+/*!
+Derive macro for the `Convertible` trait of `opencc-rust-windows`. Use it through the `derive` feature of that
+crate instead of depending on this crate directly.
+*/
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Error, Field, Fields, GenericParam, Ident, Result, parse_macro_input,
+    parse_quote, punctuated::Punctuated, token::Comma,
+};
+
+/// Derive `Convertible` by converting every field, except the fields marked with `#[opencc(skip)]`.
+///
+/// Every type parameter gets a `Convertible` bound.
+#[proc_macro_derive(Convertible, attributes(opencc))]
+pub fn derive_convertible(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream> {
+    let arms = match &input.data {
+        Data::Struct(data) => vec![arm(quote!(Self), &data.fields)?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let name = &variant.ident;
+
+                arm(quote!(Self::#name), &variant.fields)
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Convertible cannot be derived for unions",
+            ));
+        }
+    };
+
+    for param in &mut input.generics.params {
+        if let GenericParam::Type(param) = param {
+            param
+                .bounds
+                .push(parse_quote!(::opencc_rust_windows::Convertible));
+        }
+    }
+
+    // A match on a reference to an empty enum needs a dereference to be exhaustive.
+    let body = if arms.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#arms)* })
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::opencc_rust_windows::Convertible for #name #type_generics #where_clause {
+            #[allow(unused_variables, unreachable_code)]
+            fn convert_strings<__C: ::opencc_rust_windows::Converter + ?::core::marker::Sized>(
+                &mut self,
+                converter: &__C,
+            ) -> ::core::result::Result<(), ::opencc_rust_windows::OpenCCError> {
+                #body
+
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Build a match arm that binds the converted fields of a struct or a variant, and converts them.
+fn arm(path: TokenStream, fields: &Fields) -> Result<TokenStream> {
+    let mut bindings = Vec::new();
+
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let mut names = Vec::new();
+
+            for field in &fields.named {
+                if !skipped(field)? {
+                    names.push(field.ident.clone().unwrap());
+                }
+            }
+
+            bindings.clone_from(&names);
+
+            quote!(#path { #(#names,)* .. })
+        }
+        Fields::Unnamed(fields) => {
+            let mut patterns: Punctuated<TokenStream, Comma> = Punctuated::new();
+
+            for (i, field) in fields.unnamed.iter().enumerate() {
+                if skipped(field)? {
+                    patterns.push(quote!(_));
+                } else {
+                    let binding: Ident = format_ident!("__field{}", i);
+
+                    patterns.push(quote!(#binding));
+                    bindings.push(binding);
+                }
+            }
+
+            quote!(#path(#patterns))
+        }
+        Fields::Unit => quote!(#path),
+    };
+
+    Ok(quote! {
+        #pattern => {
+            #(::opencc_rust_windows::Convertible::convert_strings(#bindings, converter)?;)*
+        }
+    })
+}
+
+/// Whether a field has `#[opencc(skip)]`.
+fn skipped(field: &Field) -> Result<bool> {
+    let mut skip = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("opencc"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+
+                Ok(())
+            } else {
+                Err(meta.error("unsupported opencc attribute, expected `skip`"))
+            }
+        })?;
+    }
+
+    Ok(skip)
+}
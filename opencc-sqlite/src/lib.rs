@@ -0,0 +1,106 @@
+/*!
+# OpenCC SQLite Extension
+
+A SQLite loadable extension that provides Open Chinese Convert(OpenCC, 開放中文轉換) SQL functions, built on the
+static dictionaries of `opencc-rust-windows`.
+
+## Functions
+
+* `opencc_convert(text, config)` converts `text` with a default config like `'s2twp'` or `'t2s.json'`.
+* `opencc_detect(text)` returns `'simplified'`, `'traditional'` or `'mixed'` for the Chinese script of `text`,
+  or `NULL` if it has no characters that tell them apart.
+
+Both functions return `NULL` for a `NULL` text, and other non-text values are returned as they are. One converter
+is opened for each config the first time it is used, and shared by all connections in the process.
+
+## Usage
+
+```sql
+.load ./libopencc_sqlite
+SELECT opencc_convert(title, 's2twp') FROM items;
+SELECT opencc_detect(title) FROM items;
+```
+
+SQLite derives the entry point `sqlite3_openccsqlite_init` from the file name of the library.
+*/
+
+use std::{
+    os::raw::{c_char, c_int},
+    str::FromStr,
+};
+
+use opencc_rust_windows::{ConfigCache, DefaultConfig};
+use rusqlite::{
+    Connection, Error, Result, ffi,
+    functions::{Context, FunctionFlags},
+    types::{Value, ValueRef},
+};
+
+/// Entry point for SQLite to load the extension.
+///
+/// # Safety
+///
+/// This function must only be called by SQLite, with the arguments of a loadable extension entry point.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sqlite3_openccsqlite_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    unsafe { Connection::extension_init2(db, pz_err_msg, p_api, extension_init) }
+}
+
+fn extension_init(db: Connection) -> Result<bool> {
+    let flags = FunctionFlags::SQLITE_UTF8
+        | FunctionFlags::SQLITE_DETERMINISTIC
+        | FunctionFlags::SQLITE_INNOCUOUS;
+
+    db.create_scalar_function(c"opencc_convert", 2, flags, opencc_convert)?;
+    db.create_scalar_function(c"opencc_detect", 1, flags, opencc_detect)?;
+
+    Ok(false)
+}
+
+fn opencc_convert(ctx: &Context<'_>) -> Result<Value> {
+    let text = match ctx.get_raw(0) {
+        ValueRef::Text(text) => text,
+        _ => return ctx.get(0),
+    };
+
+    let config: String = ctx.get(1)?;
+
+    let text = convert(text, DefaultConfig::from_str(&config).map_err(user_error)?)?;
+
+    Ok(Value::Text(text))
+}
+
+fn opencc_detect(ctx: &Context<'_>) -> Result<Option<&'static str>> {
+    let text = match ctx.get_raw(0) {
+        ValueRef::Text(text) => text,
+        _ => return Ok(None),
+    };
+
+    // A text with simplified characters changes when it is converted to traditional, and vice versa.
+    let simplified = convert(text, DefaultConfig::S2T)?.as_bytes() != text;
+    let traditional = convert(text, DefaultConfig::T2S)?.as_bytes() != text;
+
+    Ok(match (simplified, traditional) {
+        (true, true) => Some("mixed"),
+        (true, false) => Some("simplified"),
+        (false, true) => Some("traditional"),
+        (false, false) => None,
+    })
+}
+
+fn convert(text: &[u8], config: DefaultConfig) -> Result<String> {
+    let text = std::str::from_utf8(text).map_err(|e| Error::Utf8Error(0, e))?;
+
+    ConfigCache::global()
+        .get(config)
+        .and_then(|opencc| opencc.convert(text))
+        .map_err(user_error)
+}
+
+fn user_error(error: opencc_rust_windows::OpenCCError) -> Error {
+    Error::UserFunctionError(Box::new(error))
+}
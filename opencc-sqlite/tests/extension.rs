@@ -0,0 +1,150 @@
+use std::{
+    env,
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    ptr,
+};
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_NULL: c_int = 5;
+
+#[link(name = "sqlite3")]
+unsafe extern "C" {
+    fn sqlite3_open(filename: *const c_char, db: *mut *mut c_void) -> c_int;
+    fn sqlite3_close(db: *mut c_void) -> c_int;
+    fn sqlite3_enable_load_extension(db: *mut c_void, onoff: c_int) -> c_int;
+    fn sqlite3_load_extension(
+        db: *mut c_void,
+        file: *const c_char,
+        proc: *const c_char,
+        err_msg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_prepare_v2(
+        db: *mut c_void,
+        sql: *const c_char,
+        n_byte: c_int,
+        stmt: *mut *mut c_void,
+        tail: *mut *const c_char,
+    ) -> c_int;
+    fn sqlite3_step(stmt: *mut c_void) -> c_int;
+    fn sqlite3_column_type(stmt: *mut c_void, col: c_int) -> c_int;
+    fn sqlite3_column_text(stmt: *mut c_void, col: c_int) -> *const c_char;
+    fn sqlite3_finalize(stmt: *mut c_void) -> c_int;
+    fn sqlite3_errmsg(db: *mut c_void) -> *const c_char;
+}
+
+/// An in-memory database with the extension loaded.
+struct Database(*mut c_void);
+
+impl Database {
+    fn open() -> Self {
+        // The test executable is in `target/<profile>/deps`, next to the extension library.
+        let mut library = env::current_exe().unwrap();
+        library.pop();
+        library.push(format!(
+            "{}opencc_sqlite{}",
+            env::consts::DLL_PREFIX,
+            env::consts::DLL_SUFFIX
+        ));
+
+        let library = CString::new(library.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let mut db = ptr::null_mut();
+
+            assert_eq!(SQLITE_OK, sqlite3_open(c":memory:".as_ptr(), &mut db));
+            assert_eq!(SQLITE_OK, sqlite3_enable_load_extension(db, 1));
+
+            let mut err_msg = ptr::null_mut();
+
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_load_extension(db, library.as_ptr(), ptr::null(), &mut err_msg),
+                "{}",
+                if err_msg.is_null() {
+                    ""
+                } else {
+                    CStr::from_ptr(err_msg).to_str().unwrap()
+                }
+            );
+
+            Database(db)
+        }
+    }
+
+    /// Run a query that returns a single text or `NULL` value, or return the error message.
+    fn query(&self, sql: &str) -> Result<Option<String>, String> {
+        let sql = CString::new(sql).unwrap();
+
+        unsafe {
+            let mut stmt = ptr::null_mut();
+
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_prepare_v2(self.0, sql.as_ptr(), -1, &mut stmt, ptr::null_mut())
+            );
+
+            let result = if sqlite3_step(stmt) != SQLITE_ROW {
+                Err(CStr::from_ptr(sqlite3_errmsg(self.0))
+                    .to_str()
+                    .unwrap()
+                    .to_string())
+            } else if sqlite3_column_type(stmt, 0) == SQLITE_NULL {
+                Ok(None)
+            } else {
+                Ok(Some(
+                    CStr::from_ptr(sqlite3_column_text(stmt, 0))
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                ))
+            };
+
+            sqlite3_finalize(stmt);
+
+            result
+        }
+    }
+}
+
+impl Drop for Database {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_close(self.0);
+        }
+    }
+}
+
+#[test]
+fn convert_and_detect() {
+    let db = Database::open();
+
+    assert_eq!(
+        Ok(Some("這個軟體".to_string())),
+        db.query("SELECT opencc_convert('这个软件', 's2twp')")
+    );
+    assert_eq!(
+        Ok(Some("这个软件".to_string())),
+        db.query("SELECT opencc_convert('這個軟件', 'T2S.json')")
+    );
+    assert_eq!(Ok(None), db.query("SELECT opencc_convert(NULL, 's2t')"));
+    assert_eq!(
+        Ok(Some("42".to_string())),
+        db.query("SELECT opencc_convert(42, 's2t')")
+    );
+    assert!(db.query("SELECT opencc_convert('软件', 's2xx')").is_err());
+
+    assert_eq!(
+        Ok(Some("simplified".to_string())),
+        db.query("SELECT opencc_detect('这个软件')")
+    );
+    assert_eq!(
+        Ok(Some("traditional".to_string())),
+        db.query("SELECT opencc_detect('這個軟件')")
+    );
+    assert_eq!(
+        Ok(Some("mixed".to_string())),
+        db.query("SELECT opencc_detect('这個')")
+    );
+    assert_eq!(Ok(None), db.query("SELECT opencc_detect('OpenCC')"));
+}
@@ -0,0 +1,277 @@
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::Parser;
+use opencc_rust_windows::{
+    DefaultConfig, FileStatus, OpenCC, OpenCCError, TreeOptions, TreeOutput, Utf8Mode, convert_tree,
+};
+
+/// Convert text between Traditional Chinese and Simplified Chinese with OpenCC.
+///
+/// Without inputs, or with `-` as the input, the standard input is converted to the standard output.
+/// Files are written to the standard output unless `--in-place`, `--output` or `--output-dir` is used.
+/// Directories are converted recursively and need `--in-place` or `--output-dir`.
+#[derive(Debug, Parser)]
+#[command(name = "opencc-rs", version)]
+struct Args {
+    /// A default config name like `s2twp`, or the path of a config file.
+    #[arg(short, long, default_value = "s2t")]
+    config: String,
+
+    /// Write the output to this file.
+    #[arg(short, long, conflicts_with_all = ["in_place", "output_dir"])]
+    output: Option<PathBuf>,
+
+    /// Write converted files and directories into this directory.
+    #[arg(short = 'd', long, conflicts_with = "in_place")]
+    output_dir: Option<PathBuf>,
+
+    /// Convert files and directories in place.
+    #[arg(short, long)]
+    in_place: bool,
+
+    /// Keep a copy of every file converted in place, with this suffix appended to its name.
+    /// Not supported for directories.
+    #[arg(long, requires = "in_place")]
+    backup: Option<String>,
+
+    /// Only convert files in directories that match this glob. Can be repeated. Only for directories.
+    #[arg(long)]
+    include: Vec<String>,
+
+    /// Leave out files and directories that match this glob. Can be repeated. Only for directories.
+    #[arg(long)]
+    exclude: Vec<String>,
+
+    /// Also convert the names of files and directories inside directories. Only for directories.
+    #[arg(long)]
+    rename: bool,
+
+    /// Only list the files that would change, without writing anything. Needs `--in-place` or `--output-dir`.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// The number of worker threads for directories. `0` means one per CPU.
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+
+    /// List the default config names and exit.
+    #[arg(long)]
+    list_configs: bool,
+
+    /// Input files or directories.
+    inputs: Vec<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    if args.list_configs {
+        for config in DefaultConfig::ALL {
+            println!("{}", config.get_file_name().trim_end_matches(".json"));
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("opencc-rs: {}", e);
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the conversion, returning `false` if some files in directories failed.
+fn run(args: &Args) -> Result<bool, OpenCCError> {
+    let opencc = open(&args.config)?;
+
+    let stdin = PathBuf::from("-");
+    let inputs = if args.inputs.is_empty() {
+        std::slice::from_ref(&stdin)
+    } else {
+        args.inputs.as_slice()
+    };
+
+    check_inputs(args, inputs, &stdin)?;
+
+    let mut success = true;
+
+    if !args.in_place && args.output_dir.is_none() {
+        let mut writer: Box<dyn Write> = match &args.output {
+            Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+
+        for input in inputs {
+            if input == &stdin {
+                opencc.convert_stream(io::stdin().lock(), &mut writer)?;
+            } else if input.is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "'{}' is a directory, use --in-place or --output-dir",
+                        input.display()
+                    ),
+                )
+                .into());
+            } else {
+                opencc.convert_stream(BufReader::new(fs::File::open(input)?), &mut writer)?;
+            }
+        }
+
+        writer.flush()?;
+
+        return Ok(success);
+    }
+
+    for input in inputs {
+        if input == &stdin {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the standard input cannot be converted in place or into a directory",
+            )
+            .into());
+        }
+
+        if input.is_dir() {
+            success &= convert_dir(args, input)?;
+            continue;
+        }
+
+        if args.dry_run {
+            let content = fs::read(input)?;
+
+            if opencc.convert_bytes(&content, Utf8Mode::Strict)? != content {
+                println!("{}", input.display());
+            }
+
+            continue;
+        }
+
+        match &args.output_dir {
+            Some(output_dir) => {
+                fs::create_dir_all(output_dir)?;
+                opencc.convert_file(input, output_dir.join(file_name(input)?))?;
+            }
+            None => match &args.backup {
+                Some(suffix) => {
+                    let mut backup = input.clone().into_os_string();
+                    backup.push(suffix);
+
+                    opencc.convert_file_in_place_with_backup(input, backup)?;
+                }
+                None => opencc.convert_file_in_place(input)?,
+            },
+        }
+    }
+
+    Ok(success)
+}
+
+/// Reject flags that do not apply to some of the inputs, before anything is converted.
+fn check_inputs(args: &Args, inputs: &[PathBuf], stdin: &Path) -> Result<(), OpenCCError> {
+    let invalid =
+        |message: String| Err(io::Error::new(io::ErrorKind::InvalidInput, message).into());
+
+    if args.dry_run && !args.in_place && args.output_dir.is_none() {
+        return invalid("--dry-run needs --in-place or --output-dir".to_string());
+    }
+
+    for input in inputs {
+        if input != stdin && input.is_dir() {
+            if args.backup.is_some() {
+                return invalid(format!(
+                    "'{}' is a directory, --backup is only supported for files",
+                    input.display()
+                ));
+            }
+        } else if !args.include.is_empty() || !args.exclude.is_empty() || args.rename {
+            return invalid(format!(
+                "'{}' is not a directory, --include, --exclude and --rename are only supported for directories",
+                input.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn convert_dir(args: &Args, input: &Path) -> Result<bool, OpenCCError> {
+    let output = match &args.output_dir {
+        Some(output_dir) => TreeOutput::Mirror(output_dir.join(file_name(input)?)),
+        None => TreeOutput::InPlace,
+    };
+
+    let options = TreeOptions {
+        include: args.include.clone(),
+        exclude: args.exclude.clone(),
+        output,
+        dry_run: args.dry_run,
+        threads: args.threads,
+        rename: args.rename,
+        ..TreeOptions::default()
+    };
+
+    // Every worker thread opens its own handle, so the files are converted in parallel.
+    let summary = convert_tree(input, &options, |_| Some(()), |_| open(&args.config))?;
+
+    for entry in &summary.entries {
+        let path = input.join(&entry.path);
+
+        match &entry.status {
+            FileStatus::WouldChange => println!("{}", path.display()),
+            FileStatus::Failed(e) => eprintln!("opencc-rs: {}: {}", path.display(), e),
+            _ => {}
+        }
+
+        if args.dry_run
+            && let Some(new_path) = &entry.renamed_to
+        {
+            println!("{} -> {}", path.display(), input.join(new_path).display());
+        }
+    }
+
+    for collision in &summary.collisions {
+        eprintln!(
+            "opencc-rs: not renamed, the names would collide at {}: {}",
+            input.join(&collision.target).display(),
+            collision
+                .sources
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    eprintln!("{}: {}", input.display(), summary);
+
+    Ok(summary.failed().count() == 0)
+}
+
+fn open(config: &str) -> Result<OpenCC, OpenCCError> {
+    match config.parse::<DefaultConfig>() {
+        Ok(default_config) if !Path::new(config).is_file() => {
+            OpenCC::from_default_config(default_config)
+        }
+        _ => OpenCC::new(config),
+    }
+}
+
+fn file_name(path: &Path) -> Result<&std::ffi::OsStr, OpenCCError> {
+    path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' has no file name", path.display()),
+        )
+        .into()
+    })
+}
@@ -0,0 +1,163 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use lru::LruCache;
+
+use crate::{OpenCC, OpenCCError};
+
+/// Statistics of a [`CachedOpenCC`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// The number of conversions answered from the cache.
+    pub hits: u64,
+    /// The number of conversions that called the C library.
+    pub misses: u64,
+    /// The number of entries evicted to stay within the capacity.
+    pub evictions: u64,
+    /// The number of cached entries.
+    pub entries: usize,
+    /// The total size of the cached inputs and outputs, in bytes.
+    pub size: usize,
+    /// The maximum total size, in bytes.
+    pub capacity: usize,
+}
+
+/// The LRU entries and their total size, which are updated together.
+struct Entries {
+    lru: LruCache<String, String>,
+    size: usize,
+    /// Incremented when the converter is replaced, so results of the old converter are not cached.
+    generation: u64,
+}
+
+/// An OpenCC instance that memoizes its conversions in a least recently used cache.
+///
+/// The cache is bounded by the total size in bytes of the cached inputs and outputs, and inputs whose entry
+/// is larger than the whole capacity are never cached. The instance can be shared across threads. When the
+/// config is reloaded with [`CachedOpenCC::reload`] or replaced with [`CachedOpenCC::replace`], the cache is
+/// cleared.
+pub struct CachedOpenCC {
+    config_file_path: PathBuf,
+    opencc: RwLock<Arc<OpenCC>>,
+    entries: Mutex<Entries>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl CachedOpenCC {
+    /// Create a new cached OpenCC instance through a file provided by its path, with a cache of at most
+    /// `capacity` bytes.
+    pub fn new<P: AsRef<Path>>(config_file_path: P, capacity: usize) -> Result<Self, OpenCCError> {
+        let config_file_path = config_file_path.as_ref().to_path_buf();
+        let opencc = OpenCC::new(&config_file_path)?;
+
+        Ok(CachedOpenCC {
+            config_file_path,
+            opencc: RwLock::new(Arc::new(opencc)),
+            entries: Mutex::new(Entries {
+                lru: LruCache::unbounded(),
+                size: 0,
+                generation: 0,
+            }),
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        })
+    }
+
+    /// Convert a string to another string, using the cached result if there is one.
+    pub fn convert<S: AsRef<str>>(&self, input: S) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let generation = {
+            let mut entries = self.entries.lock().unwrap();
+
+            if let Some(output) = entries.lru.get(input) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+
+                return Ok(output.clone());
+            }
+
+            entries.generation
+        };
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let opencc = Arc::clone(&self.opencc.read().unwrap());
+        let output = opencc.convert_cow(input)?.into_owned();
+
+        let entry_size = input.len() + output.len();
+
+        if entry_size <= self.capacity {
+            let mut entries = self.entries.lock().unwrap();
+
+            if entries.generation == generation && !entries.lru.contains(input) {
+                entries.lru.put(input.to_string(), output.clone());
+                entries.size += entry_size;
+
+                while entries.size > self.capacity {
+                    let Some((key, value)) = entries.lru.pop_lru() else {
+                        break;
+                    };
+
+                    entries.size -= key.len() + value.len();
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Reopen the config from its file, and clear the cache.
+    ///
+    /// # Errors
+    ///
+    /// If the config cannot be opened, an error is returned, and the current converter and cache are kept.
+    pub fn reload(&self) -> Result<(), OpenCCError> {
+        self.replace(OpenCC::new(&self.config_file_path)?);
+
+        Ok(())
+    }
+
+    /// Replace the converter with `opencc`, and clear the cache.
+    pub fn replace(&self, opencc: OpenCC) {
+        let mut entries = self.entries.lock().unwrap();
+
+        *self.opencc.write().unwrap() = Arc::new(opencc);
+
+        entries.generation += 1;
+        entries.lru.clear();
+        entries.size = 0;
+    }
+
+    /// Remove every cached entry. The statistics are kept.
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.lru.clear();
+        entries.size = 0;
+    }
+
+    /// Get the statistics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.entries.lock().unwrap();
+
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: entries.lru.len(),
+            size: entries.size,
+            capacity: self.capacity,
+        }
+    }
+}
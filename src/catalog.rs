@@ -0,0 +1,1052 @@
+use std::ops::Range;
+
+use crate::{
+    DefaultConfig, OpenCC, OpenCCError,
+    html::{StartTag, find_end, is_chinese_language_tag, parse_start_tag},
+    protect::printf_end,
+    utf8::char_len,
+};
+
+/// Inline XLIFF elements whose content is native code instead of text.
+const XLIFF_CODE_ELEMENTS: [&str; 8] = ["ph", "bpt", "ept", "it", "x", "bx", "ex", "sub"];
+
+/// Resource file formats supported by [`OpenCC::convert_catalog`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CatalogFormat {
+    /// Gettext PO and POT files.
+    Po,
+    /// Fluent FTL files.
+    Fluent,
+    /// Android resource files, like `strings.xml`.
+    AndroidStrings,
+    /// Apple `.strings` files.
+    AppleStrings,
+    /// XLIFF 1.2 and 2.x files.
+    Xliff,
+    /// Flutter ARB files.
+    Arb,
+}
+
+impl CatalogFormat {
+    /// Get the format of a resource file from its extension, like `po` or `xlf`, ignoring case.
+    ///
+    /// The `xml` extension is taken as an Android resource file.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "po" | "pot" => Some(CatalogFormat::Po),
+            "ftl" => Some(CatalogFormat::Fluent),
+            "xml" => Some(CatalogFormat::AndroidStrings),
+            "strings" => Some(CatalogFormat::AppleStrings),
+            "xlf" | "xliff" => Some(CatalogFormat::Xliff),
+            "arb" => Some(CatalogFormat::Arb),
+            _ => None,
+        }
+    }
+}
+
+/// Options for [`OpenCC::convert_catalog`].
+#[derive(Debug, Clone, Default)]
+pub struct CatalogOptions {
+    /// The language tag that replaces the Chinese locale headers, like `zh-TW`. Underscores are used
+    /// instead of hyphens when the replaced header uses them, like `zh_TW`. The default is `None`, which
+    /// leaves the headers as they are.
+    pub language_tag: Option<String>,
+    /// Mark the entries whose translations changed as fuzzy in PO files, and as needing review in XLIFF
+    /// files. The other formats have no such marker. The default is `false`.
+    pub mark_changed: bool,
+}
+
+impl CatalogOptions {
+    /// Create the default options with the locale headers replaced by the target language tag of `config`.
+    pub fn for_config(config: DefaultConfig) -> Self {
+        CatalogOptions {
+            language_tag: config.target_language_tag().map(String::from),
+            ..CatalogOptions::default()
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert the translations of an i18n resource file, like a gettext catalog.
+    ///
+    /// Only the translated values are converted, never the source strings or the keys. ICU MessageFormat
+    /// arguments, Fluent placeables, printf conversions and escape sequences are left as they are, while
+    /// the messages of `plural` and `select` arguments and of Fluent variants are converted. Everything
+    /// else is copied byte for byte.
+    ///
+    /// The locale headers are the `Language` header of PO files, the `target-language`, `trgLang` and
+    /// `xml:lang` attributes of XLIFF files and the `@@locale` value of ARB files. The other formats keep
+    /// their locale in the path of the file.
+    ///
+    /// # Errors
+    ///
+    /// If an ARB file is not a JSON object, or if the C library conversion fails, an error is returned.
+    pub fn convert_catalog<S: AsRef<str>>(
+        &self,
+        input: S,
+        format: CatalogFormat,
+        options: &CatalogOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut catalog = Catalog {
+            input,
+            language_tag: options.language_tag.as_deref(),
+            entries: Vec::new(),
+            replacements: Vec::new(),
+        };
+
+        match format {
+            CatalogFormat::Po => catalog.po(),
+            CatalogFormat::Fluent => catalog.fluent(),
+            CatalogFormat::AndroidStrings => catalog.xml(false),
+            CatalogFormat::AppleStrings => catalog.apple_strings(),
+            CatalogFormat::Xliff => catalog.xml(true),
+            CatalogFormat::Arb => {
+                catalog
+                    .arb()
+                    .map_err(|message| OpenCCError::InvalidDocument {
+                        format: "ARB",
+                        message,
+                    })?
+            }
+        }
+
+        catalog.apply(self, options.mark_changed)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Syntax {
+    /// ICU MessageFormat, which also covers simple `{name}` placeholders.
+    Icu,
+    Fluent,
+}
+
+/// A message, whose text can be split into several pieces, like the lines of a PO string.
+struct Message {
+    pieces: Vec<Range<usize>>,
+    syntax: Syntax,
+}
+
+impl Message {
+    fn new(range: Range<usize>, syntax: Syntax) -> Self {
+        Message {
+            pieces: vec![range],
+            syntax,
+        }
+    }
+}
+
+struct Entry {
+    messages: Vec<Message>,
+    /// The edit that marks the entry when its translation changed.
+    mark: Option<(Range<usize>, String)>,
+}
+
+struct Catalog<'a> {
+    input: &'a str,
+    language_tag: Option<&'a str>,
+    entries: Vec<Entry>,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+impl Catalog<'_> {
+    fn apply(self, opencc: &OpenCC, mark_changed: bool) -> Result<String, OpenCCError> {
+        let input = self.input;
+        let mut edits = self.replacements;
+
+        for entry in self.entries {
+            let mut changed = false;
+
+            for message in entry.messages {
+                for parts in message_runs(input, &message) {
+                    let source: String = parts.iter().map(|part| &input[part.clone()]).collect();
+                    let converted = opencc.convert_cow(&source)?;
+
+                    if converted != source {
+                        changed = true;
+                        split_run(input, parts, &converted, &mut edits);
+                    }
+                }
+            }
+
+            if changed
+                && mark_changed
+                && let Some(mark) = entry.mark
+            {
+                edits.push(mark);
+            }
+        }
+
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for (range, replacement) in edits {
+            output.push_str(&input[last..range.start]);
+            output.push_str(&replacement);
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+
+    /// Replace the locale header at `range` if it holds a Chinese language tag.
+    fn language(&mut self, range: Range<usize>) {
+        let Some(language_tag) = self.language_tag else {
+            return;
+        };
+
+        let value = &self.input[range.clone()];
+
+        if !is_chinese_language_tag(value.trim()) {
+            return;
+        }
+
+        let language_tag = if value.contains('_') {
+            language_tag.replace('-', "_")
+        } else {
+            language_tag.to_string()
+        };
+
+        if value.trim() != language_tag {
+            let start = range.start + (value.len() - value.trim_start().len());
+
+            self.replacements
+                .push((start..start + value.trim().len(), language_tag));
+        }
+    }
+
+    fn po(&mut self) {
+        let input = self.input;
+        let mut entry_lines: Vec<(usize, &str)> = Vec::new();
+        let mut offset = 0;
+
+        for line in input.split_inclusive('\n').chain([""]) {
+            if line.trim().is_empty() {
+                if !entry_lines.is_empty() {
+                    self.po_entry(&entry_lines);
+                    entry_lines.clear();
+                }
+            } else {
+                entry_lines.push((offset, line));
+            }
+
+            offset += line.len();
+        }
+    }
+
+    fn po_entry(&mut self, lines: &[(usize, &str)]) {
+        let mut keyword = "";
+        let mut msgid_empty = true;
+        let mut has_msgctxt = false;
+        let mut values: Vec<Vec<Range<usize>>> = Vec::new();
+        // The end of the flags comment, and whether the entry is fuzzy already.
+        let mut flags: Option<(usize, bool)> = None;
+        // Where a flags comment is inserted when there is none.
+        let mut insert_at: Option<(usize, &str)> = None;
+
+        for &(offset, line) in lines {
+            let content = line.trim_end_matches(['\n', '\r']);
+
+            if content.starts_with("#~") {
+                // Obsolete entries are kept as they are.
+                return;
+            } else if let Some(flag_list) = content.strip_prefix("#,") {
+                let fuzzy = flag_list.split(',').any(|flag| flag.trim() == "fuzzy");
+
+                flags = Some((offset + content.len(), fuzzy));
+            } else if content.starts_with("#|") {
+                insert_at.get_or_insert((offset, &line[content.len()..]));
+            } else if content.starts_with('#') {
+                continue;
+            } else {
+                let trimmed = content.trim_start();
+                let continuation = trimmed.starts_with('"');
+
+                if !continuation {
+                    keyword = trimmed.split([' ', '\t', '"']).next().unwrap_or_default();
+                    insert_at.get_or_insert((offset, &line[content.len()..]));
+                }
+
+                let (Some(open), Some(close)) = (content.find('"'), content.rfind('"')) else {
+                    continue;
+                };
+
+                if close <= open {
+                    continue;
+                }
+
+                let range = offset + open + 1..offset + close;
+
+                match keyword {
+                    "msgctxt" => has_msgctxt = true,
+                    "msgid" if !range.is_empty() => msgid_empty = false,
+                    _ if keyword.starts_with("msgstr") => match values.last_mut() {
+                        Some(pieces) if continuation => pieces.push(range),
+                        _ => values.push(vec![range]),
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        if msgid_empty && !has_msgctxt {
+            // The header entry.
+            for range in values.into_iter().flatten() {
+                let value = &self.input[range.clone()];
+
+                if let Some(start) = value.find("Language:") {
+                    let start = start + "Language:".len();
+                    let end = value[start..]
+                        .find("\\n")
+                        .map_or(value.len(), |n| start + n);
+
+                    self.language(range.start + start..range.start + end);
+                }
+            }
+
+            return;
+        }
+
+        let mark = match (flags, insert_at) {
+            (Some((_, true)), _) => None,
+            (Some((end, false)), _) => Some((end..end, ", fuzzy".to_string())),
+            (None, Some((offset, line_ending))) => Some((
+                offset..offset,
+                format!(
+                    "#, fuzzy{}",
+                    if line_ending.is_empty() {
+                        "\n"
+                    } else {
+                        line_ending
+                    }
+                ),
+            )),
+            (None, None) => None,
+        };
+
+        self.entries.push(Entry {
+            messages: values
+                .into_iter()
+                .map(|pieces| Message {
+                    pieces,
+                    syntax: Syntax::Icu,
+                })
+                .collect(),
+            mark,
+        });
+    }
+
+    fn fluent(&mut self) {
+        let input = self.input;
+        let mut value: Option<Range<usize>> = None;
+        let mut offset = 0;
+
+        for line in input.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let content_end = offset + content.len();
+            let indented = content.starts_with([' ', '\t']);
+            let trimmed = content.trim_start();
+
+            // The start of the value of a message, a term or an attribute, after its `=`.
+            let new_value = if (indented && trimmed.starts_with('.'))
+                || (!indented && content.starts_with(|c: char| c.is_ascii_alphabetic() || c == '-'))
+            {
+                content.find('=').map(|n| {
+                    let start = n + 1;
+
+                    offset + start + (content[start..].len() - content[start..].trim_start().len())
+                })
+            } else {
+                None
+            };
+
+            if let Some(start) = new_value {
+                self.fluent_value(value.take());
+                value = Some(start..content_end);
+            } else if indented && !trimmed.is_empty() {
+                if let Some(value) = &mut value {
+                    value.end = content_end;
+                }
+            } else if !trimmed.is_empty() {
+                // A comment or a line that is not part of a value.
+                self.fluent_value(value.take());
+            }
+
+            offset += line.len();
+        }
+
+        self.fluent_value(value);
+    }
+
+    fn fluent_value(&mut self, value: Option<Range<usize>>) {
+        if let Some(range) = value
+            && !range.is_empty()
+        {
+            self.entries.push(Entry {
+                messages: vec![Message::new(range, Syntax::Fluent)],
+                mark: None,
+            });
+        }
+    }
+
+    fn apple_strings(&mut self) {
+        let bytes = self.input.as_bytes();
+        let mut i = 0;
+        let mut after_equals = false;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i = find_end(self.input, i + 2, "*/");
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    i = self.input[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                }
+                b'"' => {
+                    let start = i + 1;
+                    let end = string_end(bytes, start);
+
+                    if after_equals {
+                        self.entries.push(Entry {
+                            messages: vec![Message::new(start..end, Syntax::Icu)],
+                            mark: None,
+                        });
+                    }
+
+                    after_equals = false;
+                    i = (end + 1).min(bytes.len());
+                }
+                b'=' => {
+                    after_equals = true;
+                    i += 1;
+                }
+                b';' => {
+                    after_equals = false;
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    fn arb(&mut self) -> Result<(), String> {
+        let bytes = self.input.as_bytes();
+        let skip_whitespace = |mut i: usize| {
+            while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+                i += 1;
+            }
+
+            i
+        };
+
+        let mut i = skip_whitespace(0);
+
+        if bytes.get(i) != Some(&b'{') {
+            return Err("the document is not a JSON object".into());
+        }
+
+        i += 1;
+
+        loop {
+            i = skip_whitespace(i);
+
+            match bytes.get(i) {
+                Some(b'"') => {}
+                Some(b'}') | None => return Ok(()),
+                Some(_) => return Err(format!("expected a key at byte {}", i)),
+            }
+
+            let key = i + 1..string_end(bytes, i + 1);
+
+            i = skip_whitespace(key.end + 1);
+
+            if bytes.get(i) != Some(&b':') {
+                return Err(format!("expected ':' at byte {}", i));
+            }
+
+            i = skip_whitespace(i + 1);
+
+            let key = &self.input[key];
+
+            if bytes.get(i) == Some(&b'"') {
+                let value = i + 1..string_end(bytes, i + 1);
+
+                i = (value.end + 1).min(bytes.len());
+
+                if key == "@@locale" {
+                    self.language(value);
+                } else if !key.starts_with('@') {
+                    self.entries.push(Entry {
+                        messages: vec![Message::new(value, Syntax::Icu)],
+                        mark: None,
+                    });
+                }
+            } else {
+                i = json_value_end(bytes, i);
+            }
+
+            i = skip_whitespace(i);
+
+            if bytes.get(i) == Some(&b',') {
+                i += 1;
+            }
+        }
+    }
+
+    /// Read an Android resource file, or an XLIFF file if `xliff` is `true`.
+    fn xml(&mut self, xliff: bool) {
+        let input = self.input;
+        let mut i = 0;
+        // The names of the open elements.
+        let mut stack: Vec<String> = Vec::new();
+        // The depths of the open value element and of the open element whose content is skipped.
+        let mut value_depth: Option<usize> = None;
+        let mut skip_depth: Option<usize> = None;
+        let mut entry: Option<Entry> = None;
+        let mut version2 = false;
+        // The mark of the open XLIFF 2 segment.
+        let mut segment_mark: Option<(Range<usize>, String)> = None;
+
+        while i < input.len() {
+            let start = input[i..].find('<').map_or(input.len(), |n| i + n);
+
+            if let Some(entry) = &mut entry
+                && skip_depth.is_none()
+                && start > i
+            {
+                entry.messages[0].pieces.push(i..start);
+            }
+
+            if start == input.len() {
+                break;
+            }
+
+            let rest = &input[start..];
+
+            if rest.starts_with("<!--") {
+                i = find_end(input, start + 4, "-->");
+            } else if rest.starts_with("<![CDATA[") {
+                let content_start = start + 9;
+                let content_end = input[content_start..]
+                    .find("]]>")
+                    .map_or(input.len(), |n| content_start + n);
+
+                if let Some(entry) = &mut entry
+                    && skip_depth.is_none()
+                {
+                    entry.messages[0].pieces.push(content_start..content_end);
+                }
+
+                i = find_end(input, content_end, "]]>");
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                i = find_end(input, start + 2, ">");
+            } else if let Some(end_tag) = rest.strip_prefix("</") {
+                let name = end_tag
+                    .split(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+
+                if let Some(position) = stack.iter().rposition(|n| *n == name) {
+                    stack.truncate(position);
+                }
+
+                if skip_depth.is_some_and(|depth| depth >= stack.len()) {
+                    skip_depth = None;
+                }
+
+                if value_depth.is_some_and(|depth| depth >= stack.len()) {
+                    value_depth = None;
+                    self.entries.extend(entry.take());
+                }
+
+                i = find_end(input, start + 2, ">");
+            } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                let tag = parse_start_tag(input, start);
+                let depth = stack.len();
+
+                let attribute = |name: &str| {
+                    tag.attributes
+                        .iter()
+                        .find(|a| input[a.name.clone()].eq_ignore_ascii_case(name))
+                        .and_then(|a| a.value.clone())
+                };
+
+                let mut opens_value = false;
+
+                if xliff {
+                    match tag.name.as_str() {
+                        "xliff" => {
+                            version2 =
+                                attribute("version").is_some_and(|v| !input[v].starts_with('1'));
+
+                            if let Some(value) = attribute("trgLang") {
+                                self.language(value);
+                            }
+                        }
+                        "file" => {
+                            if let Some(value) = attribute("target-language") {
+                                self.language(value);
+                            }
+                        }
+                        "segment" => {
+                            segment_mark = Some(state_mark(input, start, &tag, "translated"));
+                        }
+                        "target" if entry.is_none() => {
+                            if let Some(value) = attribute("xml:lang") {
+                                self.language(value);
+                            }
+
+                            opens_value = true;
+                        }
+                        name if entry.is_some()
+                            && skip_depth.is_none()
+                            && XLIFF_CODE_ELEMENTS.contains(&name) =>
+                        {
+                            skip_depth = Some(depth);
+                        }
+                        _ => {}
+                    }
+                } else {
+                    let translatable =
+                        attribute("translatable").is_none_or(|v| &input[v] != "false");
+
+                    if !translatable && skip_depth.is_none() {
+                        skip_depth = Some(depth);
+                    } else if entry.is_none()
+                        && skip_depth.is_none()
+                        && (tag.name == "string"
+                            || (tag.name == "item" && stack.last().is_none_or(|p| p != "style")))
+                    {
+                        opens_value = true;
+                    } else if entry.is_some() && skip_depth.is_none() && tag.name == "xliff:g" {
+                        skip_depth = Some(depth);
+                    }
+                }
+
+                if opens_value {
+                    let mark = if !xliff {
+                        None
+                    } else if version2 {
+                        segment_mark.clone()
+                    } else {
+                        Some(state_mark(input, start, &tag, "needs-review-translation"))
+                    };
+
+                    entry = Some(Entry {
+                        messages: vec![Message {
+                            pieces: Vec::new(),
+                            syntax: Syntax::Icu,
+                        }],
+                        mark,
+                    });
+                    value_depth = Some(depth);
+                }
+
+                if tag.self_closing {
+                    if skip_depth == Some(depth) {
+                        skip_depth = None;
+                    }
+
+                    if value_depth == Some(depth) {
+                        value_depth = None;
+                        self.entries.extend(entry.take());
+                    }
+                } else {
+                    stack.push(tag.name);
+                }
+
+                i = tag.end;
+            } else {
+                // A `<` that does not start any markup is text.
+                if let Some(entry) = &mut entry
+                    && skip_depth.is_none()
+                {
+                    entry.messages[0].pieces.push(start..start + 1);
+                }
+
+                i = start + 1;
+            }
+        }
+
+        self.entries.extend(entry);
+    }
+}
+
+/// Create the edit that sets the `state` attribute of a start tag beginning at `start` to `state`.
+fn state_mark(input: &str, start: usize, tag: &StartTag, state: &str) -> (Range<usize>, String) {
+    match tag
+        .attributes
+        .iter()
+        .find(|a| input[a.name.clone()].eq_ignore_ascii_case("state"))
+        .and_then(|a| a.value.clone())
+    {
+        Some(value) => (value, state.to_string()),
+        None => {
+            let name_end = start + 1 + tag.name.len();
+
+            (name_end..name_end, format!(" state=\"{}\"", state))
+        }
+    }
+}
+
+/// Find the closing quote of a string with backslash escapes whose content starts at `start`.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => return i,
+            b'\\' => i += 2,
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+/// Find the end of a JSON value that is not a string, starting at `start`.
+fn json_value_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = string_end(bytes, i + 1),
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return i,
+            b'}' | b']' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            b',' if depth == 0 => return i,
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// Split the converted text of a run back into the parts of the run, so that the run keeps its
+/// layout across the pieces of its message.
+///
+/// The parts keep their lengths in characters when the conversion kept the length of the run.
+/// Otherwise the first part takes the whole converted text and the other parts are emptied.
+fn split_run(
+    input: &str,
+    parts: Vec<Range<usize>>,
+    converted: &str,
+    edits: &mut Vec<(Range<usize>, String)>,
+) {
+    let lengths: Vec<usize> = parts
+        .iter()
+        .map(|part| input[part.clone()].chars().count())
+        .collect();
+    let same_length = lengths.iter().sum::<usize>() == converted.chars().count();
+
+    let mut rest = converted;
+
+    for (i, (part, length)) in parts.into_iter().zip(lengths).enumerate() {
+        let end = if same_length {
+            rest.char_indices()
+                .nth(length)
+                .map_or(rest.len(), |(end, _)| end)
+        } else if i == 0 {
+            rest.len()
+        } else {
+            0
+        };
+
+        let (text, tail) = rest.split_at(end);
+
+        if *text != input[part.clone()] {
+            edits.push((part, text.to_string()));
+        }
+
+        rest = tail;
+    }
+}
+
+/// Find the runs of the text of a message in `input`, leaving out its syntax, escape sequences and
+/// printf conversions. A run that crosses pieces of the message is made of one part per piece.
+fn message_runs(input: &str, message: &Message) -> Vec<Vec<Range<usize>>> {
+    let source: String = message
+        .pieces
+        .iter()
+        .map(|piece| &input[piece.clone()])
+        .collect();
+    let bytes = source.as_bytes();
+
+    let mut parser = MessageParser {
+        bytes,
+        i: 0,
+        syntax: message.syntax,
+        ranges: Vec::new(),
+    };
+
+    parser.pattern(false, false);
+
+    // The runs in `source`, split by escape sequences and printf conversions.
+    let mut runs = Vec::new();
+
+    for range in parser.ranges {
+        let mut run_start = range.start;
+        let mut i = range.start;
+
+        while i < range.end {
+            let skip_end = match bytes[i] {
+                b'\\' => {
+                    Some((i + 1 + bytes.get(i + 1).map_or(0, |&b| char_len(b))).min(range.end))
+                }
+                b'%' if bytes.get(i + 1) == Some(&b'%') => Some(i + 2),
+                b'%' => printf_end(&bytes[..range.end], i),
+                _ => None,
+            };
+
+            match skip_end {
+                Some(end) => {
+                    runs.push(run_start..i);
+                    run_start = end;
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+
+        runs.push(run_start..range.end);
+    }
+
+    // Map the runs back to the pieces, giving the runs that cross pieces one part per piece.
+    runs.into_iter()
+        .map(|run| {
+            let mut parts = Vec::new();
+            let mut piece_start = 0;
+
+            for piece in &message.pieces {
+                let piece_end = piece_start + piece.len();
+                let start = run.start.max(piece_start);
+                let end = run.end.min(piece_end);
+
+                if start < end {
+                    parts.push(piece.start + start - piece_start..piece.start + end - piece_start);
+                }
+
+                piece_start = piece_end;
+            }
+
+            parts
+        })
+        .filter(|parts| !parts.is_empty())
+        .collect()
+}
+
+/// Finds the text of an ICU or Fluent message.
+struct MessageParser<'a> {
+    bytes: &'a [u8],
+    i: usize,
+    syntax: Syntax,
+    ranges: Vec<Range<usize>>,
+}
+
+impl MessageParser<'_> {
+    /// Read the text of a message up to its end, which is the closing `}` of a nested message.
+    fn pattern(&mut self, nested: bool, plural: bool) {
+        let bytes = self.bytes;
+        let icu = self.syntax == Syntax::Icu;
+        let mut run_start = self.i;
+
+        while self.i < bytes.len() {
+            let next = bytes.get(self.i + 1).copied();
+
+            match bytes[self.i] {
+                b'{' => {
+                    self.text(run_start..self.i);
+
+                    if icu {
+                        self.icu_argument();
+                    } else {
+                        self.fluent_placeable();
+                    }
+
+                    run_start = self.i;
+                }
+                b'}' if nested => break,
+                // A quoted literal, which can contain braces.
+                b'\'' if icu && next.is_some_and(|b| b"{}#|".contains(&b)) => {
+                    self.i = bytes[self.i + 1..]
+                        .iter()
+                        .position(|&b| b == b'\'')
+                        .map_or(bytes.len(), |n| self.i + n + 2);
+                }
+                b'#' if icu && plural => {
+                    self.text(run_start..self.i);
+                    self.i += 1;
+                    run_start = self.i;
+                }
+                // A Fluent variant ends before the next variant key or the end of its select expression.
+                b'\n' if !icu && nested => {
+                    let line = bytes[self.i + 1..].trim_ascii_start();
+
+                    if line.starts_with(b"[") || line.starts_with(b"*") || line.starts_with(b"}") {
+                        break;
+                    }
+
+                    self.i += 1;
+                }
+                _ => self.i += 1,
+            }
+        }
+
+        self.text(run_start..self.i);
+    }
+
+    /// Read an ICU argument, whose `{` is at the current position.
+    fn icu_argument(&mut self) {
+        let bytes = self.bytes;
+        let start = self.i + 1;
+
+        let Some(name_end) = bytes[start..]
+            .iter()
+            .position(|&b| b == b',' || b == b'}')
+            .map(|n| start + n)
+        else {
+            self.i = bytes.len();
+            return;
+        };
+
+        if bytes[name_end] == b'}' {
+            self.i = name_end + 1;
+            return;
+        }
+
+        let type_start = name_end + 1;
+        let type_end = bytes[type_start..]
+            .iter()
+            .position(|&b| b == b',' || b == b'}')
+            .map_or(bytes.len(), |n| type_start + n);
+
+        let kind = bytes[type_start..type_end].trim_ascii();
+
+        if !matches!(kind, b"plural" | b"selectordinal" | b"select")
+            || bytes.get(type_end) != Some(&b',')
+        {
+            self.i = brace_end(bytes, start);
+            return;
+        }
+
+        self.i = type_end + 1;
+
+        loop {
+            match bytes.get(self.i) {
+                None => return,
+                Some(b'}') => {
+                    self.i += 1;
+                    return;
+                }
+                Some(b'{') => {
+                    self.i += 1;
+                    self.pattern(true, kind != b"select");
+
+                    if bytes.get(self.i) == Some(&b'}') {
+                        self.i += 1;
+                    }
+                }
+                // The selectors, like `one`, `=0` or `offset:1`.
+                Some(_) => self.i += 1,
+            }
+        }
+    }
+
+    /// Read a Fluent placeable, whose `{` is at the current position.
+    fn fluent_placeable(&mut self) {
+        let bytes = self.bytes;
+        let mut depth = 0;
+        let mut i = self.i + 1;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => i = string_end(bytes, i + 1),
+                b'{' => depth += 1,
+                b'}' if depth == 0 => {
+                    self.i = i + 1;
+                    return;
+                }
+                b'}' => depth -= 1,
+                b'-' if depth == 0 && bytes.get(i + 1) == Some(&b'>') => {
+                    self.i = i + 2;
+                    self.fluent_variants();
+                    return;
+                }
+                _ => {}
+            }
+
+            i += 1;
+        }
+
+        self.i = bytes.len();
+    }
+
+    /// Read the variants of a Fluent select expression, up to its closing `}`.
+    fn fluent_variants(&mut self) {
+        let bytes = self.bytes;
+
+        loop {
+            while bytes
+                .get(self.i)
+                .is_some_and(|b| b.is_ascii_whitespace() || *b == b'*')
+            {
+                self.i += 1;
+            }
+
+            match bytes.get(self.i) {
+                None => return,
+                Some(b'}') => {
+                    self.i += 1;
+                    return;
+                }
+                Some(b'[') => {
+                    self.i = bytes[self.i..]
+                        .iter()
+                        .position(|&b| b == b']')
+                        .map_or(bytes.len(), |n| self.i + n + 1);
+                    self.pattern(true, false);
+                }
+                Some(_) => self.i += 1,
+            }
+        }
+    }
+
+    fn text(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.ranges.push(range);
+        }
+    }
+}
+
+/// Find the end of the braces whose content starts at `start`, after the opening `{`.
+fn brace_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+
+    for (i, &b) in bytes[start..].iter().enumerate() {
+        match b {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return start + i + 1,
+            b'}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    bytes.len()
+}
@@ -0,0 +1,92 @@
+#[cfg(feature = "static-dictionaries")]
+use std::sync::LazyLock;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::{DefaultConfig, OpenCC, OpenCCError};
+
+#[cfg(feature = "static-dictionaries")]
+static GLOBAL: LazyLock<ConfigCache> = LazyLock::new(ConfigCache::with_static_dictionaries);
+
+/// Where a [`ConfigCache`] opens its configs from.
+#[derive(Debug, Clone)]
+enum ConfigSource {
+    Directory(PathBuf),
+    #[cfg(feature = "static-dictionaries")]
+    StaticDictionaries,
+}
+
+/// A cache of OpenCC handles keyed by [`DefaultConfig`], so switching between configs stays cheap.
+///
+/// Each config is opened the first time it is used and kept until the cache is dropped or cleared. The
+/// cache can be shared across threads.
+pub struct ConfigCache {
+    source: ConfigSource,
+    handles: Mutex<HashMap<DefaultConfig, Arc<OpenCC>>>,
+}
+
+impl ConfigCache {
+    /// Create a cache that opens the configs in `config_dir`, by their [`DefaultConfig::get_file_name`].
+    pub fn new<P: Into<PathBuf>>(config_dir: P) -> Self {
+        ConfigCache {
+            source: ConfigSource::Directory(config_dir.into()),
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a cache that opens the configs with [`OpenCC::from_default_config`].
+    #[cfg(feature = "static-dictionaries")]
+    pub fn with_static_dictionaries() -> Self {
+        ConfigCache {
+            source: ConfigSource::StaticDictionaries,
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the cache shared by the whole process, which opens the configs with the static dictionaries.
+    #[cfg(feature = "static-dictionaries")]
+    pub fn global() -> &'static ConfigCache {
+        &GLOBAL
+    }
+
+    /// Get the handle of `config`, opening it if it is not in the cache yet.
+    ///
+    /// # Errors
+    ///
+    /// If the config cannot be opened, an error is returned and nothing is cached.
+    pub fn get(&self, config: DefaultConfig) -> Result<Arc<OpenCC>, OpenCCError> {
+        let mut handles = self.handles.lock().unwrap();
+
+        if let Some(opencc) = handles.get(&config) {
+            return Ok(Arc::clone(opencc));
+        }
+
+        let opencc = Arc::new(match &self.source {
+            ConfigSource::Directory(dir) => OpenCC::new(dir.join(config.get_file_name()))?,
+            #[cfg(feature = "static-dictionaries")]
+            ConfigSource::StaticDictionaries => OpenCC::from_default_config(config)?,
+        });
+
+        handles.insert(config, Arc::clone(&opencc));
+
+        Ok(opencc)
+    }
+
+    /// Get the number of configs that are open.
+    pub fn len(&self) -> usize {
+        self.handles.lock().unwrap().len()
+    }
+
+    /// Whether no config is open.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Close every cached handle. Handles that are still in use are closed when they are dropped.
+    pub fn clear(&self) {
+        self.handles.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{BufRead, Write},
+    sync::Arc,
+};
+
+use crate::{OpenCC, OpenCCError, file::convert_chunks};
+
+/// A text converter, so code can be generic over [`OpenCC`] and other backends such as
+/// [`MappingConverter`] in tests.
+///
+/// Only [`Converter::convert_cow`] has to be implemented. The other methods are built on it, and the trait
+/// is object safe.
+pub trait Converter {
+    /// Convert a string, borrowing the input when nothing changes.
+    ///
+    /// # Errors
+    ///
+    /// If the conversion fails, an error is returned.
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError>;
+
+    /// Convert a string to another string.
+    ///
+    /// # Errors
+    ///
+    /// If the conversion fails, an error is returned.
+    #[inline]
+    fn convert(&self, input: &str) -> Result<String, OpenCCError> {
+        Ok(self.convert_cow(input)?.into_owned())
+    }
+
+    /// Convert a string and append the result to `output`.
+    ///
+    /// # Errors
+    ///
+    /// If the conversion fails, an error is returned and `output` is left unchanged.
+    #[inline]
+    fn convert_append(&self, input: &str, output: &mut String) -> Result<(), OpenCCError> {
+        output.push_str(&self.convert_cow(input)?);
+
+        Ok(())
+    }
+
+    /// Convert everything read from `reader` and write the result to `writer`, in chunks of whole lines.
+    ///
+    /// # Errors
+    ///
+    /// If reading or writing fails, if the input is not valid UTF-8, or if the conversion fails, an error
+    /// is returned.
+    fn convert_stream(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+    ) -> Result<(), OpenCCError> {
+        convert_chunks(reader, writer, |chunk, writer| {
+            let text = std::str::from_utf8(chunk)
+                .map_err(|e| OpenCCError::InputInvalidUtf8(e.valid_up_to()))?;
+
+            writer.write_all(self.convert_cow(text)?.as_bytes())?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Converter for OpenCC {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        OpenCC::convert_cow(self, input)
+    }
+
+    #[inline]
+    fn convert(&self, input: &str) -> Result<String, OpenCCError> {
+        OpenCC::convert(self, input)
+    }
+
+    #[inline]
+    fn convert_append(&self, input: &str, output: &mut String) -> Result<(), OpenCCError> {
+        OpenCC::convert_append(self, input, output)
+    }
+
+    #[inline]
+    fn convert_stream(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+    ) -> Result<(), OpenCCError> {
+        OpenCC::convert_stream(self, reader, writer)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl Converter for crate::CachedOpenCC {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        Ok(Cow::Owned(crate::CachedOpenCC::convert(self, input)?))
+    }
+}
+
+impl<T: Converter + ?Sized> Converter for &T {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        (**self).convert_cow(input)
+    }
+}
+
+impl<T: Converter + ?Sized> Converter for Box<T> {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        (**self).convert_cow(input)
+    }
+}
+
+impl<T: Converter + ?Sized> Converter for Arc<T> {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        (**self).convert_cow(input)
+    }
+}
+
+/// A converter that returns its input unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PassthroughConverter;
+
+impl Converter for PassthroughConverter {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        Ok(Cow::Borrowed(input))
+    }
+}
+
+/// An in-memory converter that replaces strings from a table, without the C library or dictionary files.
+///
+/// At each position, the longest matching key is replaced with its value, and the text after it is matched
+/// next, which is how OpenCC applies its phrase dictionaries.
+///
+/// ```rust
+/// use opencc_rust_windows::{Converter, MappingConverter};
+///
+/// let converter = MappingConverter::new().with("软件", "軟體").with("软", "軟");
+///
+/// assert_eq!("軟體和軟", converter.convert("软件和软").unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MappingConverter {
+    mappings: HashMap<String, String>,
+    /// The length in bytes of the longest key.
+    max_length: usize,
+}
+
+impl MappingConverter {
+    /// Create a converter without mappings.
+    #[inline]
+    pub fn new() -> Self {
+        MappingConverter::default()
+    }
+
+    /// Add a mapping, replacing the previous value of `from`. An empty `from` is ignored.
+    pub fn insert<F: Into<String>, T: Into<String>>(&mut self, from: F, to: T) {
+        let from = from.into();
+
+        if from.is_empty() {
+            return;
+        }
+
+        self.max_length = self.max_length.max(from.len());
+        self.mappings.insert(from, to.into());
+    }
+
+    /// Add a mapping, and return the converter.
+    #[inline]
+    pub fn with<F: Into<String>, T: Into<String>>(mut self, from: F, to: T) -> Self {
+        self.insert(from, to);
+
+        self
+    }
+
+    /// Get the number of mappings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Whether there are no mappings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+}
+
+impl<F: Into<String>, T: Into<String>> FromIterator<(F, T)> for MappingConverter {
+    fn from_iter<I: IntoIterator<Item = (F, T)>>(iter: I) -> Self {
+        let mut converter = MappingConverter::new();
+
+        for (from, to) in iter {
+            converter.insert(from, to);
+        }
+
+        converter
+    }
+}
+
+impl Converter for MappingConverter {
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        let mut output: Option<String> = None;
+        let mut last = 0;
+        let mut i = 0;
+
+        while i < input.len() {
+            let longest = (i + 1..=(i + self.max_length).min(input.len()))
+                .rev()
+                .filter(|&end| input.is_char_boundary(end))
+                .find_map(|end| self.mappings.get(&input[i..end]).map(|to| (end, to)));
+
+            match longest {
+                Some((end, to)) => {
+                    let output = output.get_or_insert_with(|| String::with_capacity(input.len()));
+
+                    output.push_str(&input[last..i]);
+                    output.push_str(to);
+
+                    last = end;
+                    i = end;
+                }
+                None => i += input[i..].chars().next().map_or(1, char::len_utf8),
+            }
+        }
+
+        Ok(match output {
+            Some(mut output) => {
+                output.push_str(&input[last..]);
+
+                Cow::Owned(output)
+            }
+            None => Cow::Borrowed(input),
+        })
+    }
+}
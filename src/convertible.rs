@@ -0,0 +1,243 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
+    hash::{BuildHasher, Hash},
+    mem,
+};
+
+use crate::{Converter, OpenCCError};
+
+/// A value whose strings can be converted in place.
+///
+/// The trait is implemented for strings, for the common containers of convertible values, and as a no-op
+/// for numbers and other types without text. The values of maps are converted, and their keys are left as
+/// they are. With the `derive` feature, it can be derived for structs and enums, skipping the fields marked
+/// with `#[opencc(skip)]`.
+///
+/// The strings are converted with any [`Converter`], like an [`OpenCC`](crate::OpenCC) instance or a
+/// [`MappingConverter`](crate::MappingConverter).
+pub trait Convertible {
+    /// Convert every string in this value with `converter`.
+    ///
+    /// # Errors
+    ///
+    /// If the conversion fails, an error is returned, and the strings before the failed one stay
+    /// converted.
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError>;
+
+    /// Convert every string in this value with `converter`, and return the value.
+    ///
+    /// # Errors
+    ///
+    /// If the conversion fails, an error is returned.
+    #[inline]
+    fn converted<C: Converter + ?Sized>(mut self, converter: &C) -> Result<Self, OpenCCError>
+    where
+        Self: Sized,
+    {
+        self.convert_strings(converter)?;
+
+        Ok(self)
+    }
+}
+
+impl Convertible for String {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        if let Cow::Owned(s) = converter.convert_cow(self)? {
+            *self = s;
+        }
+
+        Ok(())
+    }
+}
+
+impl Convertible for Cow<'_, str> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        if let Cow::Owned(s) = converter.convert_cow(self)? {
+            *self = Cow::Owned(s);
+        }
+
+        Ok(())
+    }
+}
+
+impl Convertible for Box<str> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        if let Cow::Owned(s) = converter.convert_cow(self)? {
+            *self = s.into_boxed_str();
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_no_op {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Convertible for $t {
+                #[inline]
+                fn convert_strings<C: Converter + ?Sized>(
+                    &mut self,
+                    _converter: &C,
+                ) -> Result<(), OpenCCError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_no_op!(
+    (),
+    bool,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+);
+
+impl<T: Convertible + ?Sized> Convertible for Box<T> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        (**self).convert_strings(converter)
+    }
+}
+
+impl<T: Convertible + ?Sized> Convertible for &mut T {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        (**self).convert_strings(converter)
+    }
+}
+
+impl<T: Convertible> Convertible for Option<T> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        match self {
+            Some(value) => value.convert_strings(converter),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Convertible> Convertible for [T] {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.iter_mut()
+            .try_for_each(|value| value.convert_strings(converter))
+    }
+}
+
+impl<T: Convertible, const N: usize> Convertible for [T; N] {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.as_mut_slice().convert_strings(converter)
+    }
+}
+
+impl<T: Convertible> Convertible for Vec<T> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.as_mut_slice().convert_strings(converter)
+    }
+}
+
+impl<T: Convertible> Convertible for VecDeque<T> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.iter_mut()
+            .try_for_each(|value| value.convert_strings(converter))
+    }
+}
+
+impl<T: Convertible> Convertible for LinkedList<T> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.iter_mut()
+            .try_for_each(|value| value.convert_strings(converter))
+    }
+}
+
+impl<K, V: Convertible, S> Convertible for HashMap<K, V, S> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.values_mut()
+            .try_for_each(|value| value.convert_strings(converter))
+    }
+}
+
+impl<K, V: Convertible> Convertible for BTreeMap<K, V> {
+    #[inline]
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        self.values_mut()
+            .try_for_each(|value| value.convert_strings(converter))
+    }
+}
+
+impl<T: Convertible + Eq + Hash, S: BuildHasher + Default> Convertible for HashSet<T, S> {
+    /// The set is rebuilt, since its values cannot be changed in place.
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        *self = mem::take(self)
+            .into_iter()
+            .map(|value| value.converted(converter))
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+}
+
+impl<T: Convertible + Ord> Convertible for BTreeSet<T> {
+    /// The set is rebuilt, since its values cannot be changed in place.
+    fn convert_strings<C: Converter + ?Sized>(&mut self, converter: &C) -> Result<(), OpenCCError> {
+        *self = mem::take(self)
+            .into_iter()
+            .map(|value| value.converted(converter))
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_tuple {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: Convertible),+> Convertible for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn convert_strings<Conv: Converter + ?Sized>(
+                    &mut self,
+                    converter: &Conv,
+                ) -> Result<(), OpenCCError> {
+                    let ($($name,)+) = self;
+
+                    $($name.convert_strings(converter)?;)+
+
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F)
+);
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use encoding_rs::{BIG5, EUC_JP, EncoderResult, Encoding, GBK};
+
+use crate::{
+    OpenCC, OpenCCError,
+    dictionary::config_dictionaries,
+    encoding::{hkscs_map, is_cp950},
+};
+
+/// Character repertoires that converted text can be checked against.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Repertoire {
+    /// GB 2312, the double-byte area of EUC-CN
+    Gb2312,
+    /// GBK (CP936)
+    Gbk,
+    /// Big5 (CP950) without the Hong Kong Supplementary Character Set
+    Big5,
+    /// Big5 with the Hong Kong Supplementary Character Set (HKSCS-2008)
+    Big5Hkscs,
+    /// JIS X 0208, the double-byte area of EUC-JP without vendor extensions
+    JisX0208,
+}
+
+impl Repertoire {
+    /// Get the name of this repertoire.
+    pub fn name(self) -> &'static str {
+        match self {
+            Repertoire::Gb2312 => "GB2312",
+            Repertoire::Gbk => "GBK",
+            Repertoire::Big5 => "Big5",
+            Repertoire::Big5Hkscs => "Big5-HKSCS",
+            Repertoire::JisX0208 => "JIS X 0208",
+        }
+    }
+
+    /// Check whether a character belongs to this repertoire. ASCII characters always do.
+    pub fn contains(self, c: char) -> bool {
+        if c.is_ascii() {
+            return true;
+        }
+
+        match self {
+            Repertoire::Gb2312 => matches!(
+                encode_char(GBK, c).as_deref(),
+                Some(&[0xA1..=0xA9 | 0xB0..=0xF7, 0xA1..=0xFE])
+            ),
+            Repertoire::Gbk => encode_char(GBK, c).is_some(),
+            Repertoire::Big5 => encode_char(BIG5, c).is_some_and(|bytes| is_cp950(&bytes)),
+            Repertoire::Big5Hkscs => encode_char(BIG5, c).is_some() || hkscs_map().contains_key(&c),
+            Repertoire::JisX0208 => matches!(
+                encode_char(EUC_JP, c).as_deref(),
+                Some(&[0xA1..=0xA8 | 0xB0..=0xF4, 0xA1..=0xFE])
+            ),
+        }
+    }
+}
+
+/// A character that is not in the checked repertoire.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OutOfSetCharacter {
+    /// The character.
+    pub character: char,
+    /// The byte offset of the character in the text.
+    pub offset: usize,
+    /// The line of the character, starting from 1.
+    pub line: usize,
+    /// The column of the character in characters, starting from 1.
+    pub column: usize,
+    /// In-set alternatives from the conversion dictionaries, filled by [`CoverageReport::suggest`].
+    pub suggestions: Vec<char>,
+}
+
+/// The result of [`check_coverage`].
+#[derive(Debug, Clone)]
+pub struct CoverageReport {
+    /// The repertoire the text was checked against.
+    pub repertoire: Repertoire,
+    /// Every character that is not in the repertoire, in the order they appear.
+    pub characters: Vec<OutOfSetCharacter>,
+}
+
+impl CoverageReport {
+    /// Whether every character of the text is in the repertoire.
+    pub fn is_covered(&self) -> bool {
+        self.characters.is_empty()
+    }
+
+    /// Look for in-set alternatives of the out-of-set characters in the dictionaries of the `candidates`,
+    /// for example `S2T` and `T2TW` for text that was produced by `S2T`.
+    ///
+    /// The character dictionaries list every candidate of a character, like `干` to `幹`, `乾` and `干`, even
+    /// though a conversion only uses the first one. The candidates of an out-of-set character, and the
+    /// other candidates of every character that lists it as a candidate, are added to the suggestions when
+    /// they are in the repertoire, in the order of the dictionaries.
+    ///
+    /// # Errors
+    ///
+    /// If the dictionaries of a config cannot be read, an error is returned.
+    pub fn suggest(&mut self, candidates: &[&OpenCC]) -> Result<(), OpenCCError> {
+        let mut lists: HashMap<char, Vec<Vec<char>>> = HashMap::new();
+
+        for opencc in candidates {
+            for dictionary in config_dictionaries(opencc.config_file_path())? {
+                for (key, values) in dictionary.entries {
+                    let mut chars = key.chars();
+
+                    let (Some(key), None) = (chars.next(), chars.next()) else {
+                        continue;
+                    };
+
+                    let values: Vec<char> = values
+                        .iter()
+                        .filter_map(|value| {
+                            let mut chars = value.chars();
+
+                            match (chars.next(), chars.next()) {
+                                (Some(c), None) => Some(c),
+                                _ => None,
+                            }
+                        })
+                        .collect();
+
+                    for c in values.iter().copied().chain([key]) {
+                        if self.characters.iter().any(|o| o.character == c) {
+                            lists.entry(c).or_default().push(values.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        for out_of_set in self.characters.iter_mut() {
+            let Some(lists) = lists.get(&out_of_set.character) else {
+                continue;
+            };
+
+            for c in lists.iter().flatten().copied() {
+                if c != out_of_set.character
+                    && self.repertoire.contains(c)
+                    && !out_of_set.suggestions.contains(&c)
+                {
+                    out_of_set.suggestions.push(c);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check a text against a character repertoire and report every character that is not in it.
+pub fn check_coverage<S: AsRef<str>>(text: S, repertoire: Repertoire) -> CoverageReport {
+    let mut characters = Vec::new();
+    let mut line = 1;
+    let mut column = 0;
+
+    for (offset, c) in text.as_ref().char_indices() {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+            continue;
+        }
+
+        column += 1;
+
+        if !repertoire.contains(c) {
+            characters.push(OutOfSetCharacter {
+                character: c,
+                offset,
+                line,
+                column,
+                suggestions: Vec::new(),
+            });
+        }
+    }
+
+    CoverageReport {
+        repertoire,
+        characters,
+    }
+}
+
+fn encode_char(encoding: &'static Encoding, c: char) -> Option<Vec<u8>> {
+    let mut input = [0u8; 4];
+    let mut output = [0u8; 8];
+
+    let mut encoder = encoding.new_encoder();
+
+    match encoder.encode_from_utf8_without_replacement(c.encode_utf8(&mut input), &mut output, true)
+    {
+        (EncoderResult::InputEmpty, _, written) => Some(output[..written].to_vec()),
+        _ => None,
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    io::{BufRead, Write},
+    ops::Range,
+};
+
+use crate::{OpenCC, OpenCCError};
+
+/// A column selected by [`CsvOptions::columns`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CsvColumn {
+    /// The column with this name in the header row.
+    Name(String),
+    /// The column at this index, starting from 0.
+    Index(usize),
+}
+
+/// Options for [`OpenCC::convert_csv`].
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// The byte that separates the fields. The default is `,`.
+    pub delimiter: u8,
+    /// The byte that quotes fields, or `None` if fields are never quoted. The default is `"`.
+    pub quote: Option<u8>,
+    /// Whether the first record is a header row, which is never converted. The default is `true`.
+    pub has_headers: bool,
+    /// The converted columns. The default is empty, which converts every column.
+    pub columns: Vec<CsvColumn>,
+}
+
+impl CsvOptions {
+    /// Create the default options for tab-separated values.
+    pub fn tsv() -> Self {
+        CsvOptions {
+            delimiter: b'\t',
+            ..CsvOptions::default()
+        }
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            quote: Some(b'"'),
+            has_headers: true,
+            columns: Vec::new(),
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert the selected columns of CSV or TSV data read from `reader`, and write the result to `writer`.
+    ///
+    /// The input is read one record at a time, so the memory usage only depends on the length of the
+    /// longest record. Quoted fields can contain delimiters, escaped quotes and line breaks. Only the
+    /// contents of the selected fields are converted, and everything else, including the quotes and the
+    /// line endings, is copied byte for byte.
+    ///
+    /// # Errors
+    ///
+    /// If reading or writing fails, if the input is not valid UTF-8, if a column name is not in the header
+    /// row, or if the C library conversion fails, an error is returned.
+    pub fn convert_csv<R: BufRead, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        options: &CsvOptions,
+    ) -> Result<(), OpenCCError> {
+        let mut record = Vec::new();
+        let mut position = 0;
+        // The selected column indices, or `None` for every column.
+        let mut selected: Option<Vec<usize>> = if options.has_headers {
+            None
+        } else {
+            column_indices(&options.columns, &[])?
+        };
+        let mut header = options.has_headers;
+
+        loop {
+            record.clear();
+
+            // Read lines until the record does not end inside a quoted field.
+            let fields = loop {
+                let length = record.len();
+
+                if reader.read_until(b'\n', &mut record)? == 0 {
+                    break split_record(&record, options, true);
+                }
+
+                if let Some(fields) = split_record(&record, options, false) {
+                    break Some(fields);
+                }
+
+                if record.len() == length {
+                    break None;
+                }
+            };
+
+            if record.is_empty() {
+                break;
+            }
+
+            let fields = fields.unwrap_or_default();
+
+            let text = std::str::from_utf8(&record)
+                .map_err(|e| OpenCCError::InputInvalidUtf8(position + e.valid_up_to()))?;
+
+            if header {
+                let names: Vec<String> = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let name = unquote(&text[field.clone()], options.quote);
+
+                        if i == 0 {
+                            name.trim_start_matches('\u{feff}').to_string()
+                        } else {
+                            name
+                        }
+                    })
+                    .collect();
+
+                selected = column_indices(&options.columns, &names)?;
+                header = false;
+
+                writer.write_all(&record)?;
+            } else {
+                let mut last = 0;
+
+                for (i, field) in fields.into_iter().enumerate() {
+                    if selected.as_ref().is_some_and(|s| !s.contains(&i)) {
+                        continue;
+                    }
+
+                    writer.write_all(&record[last..field.start])?;
+                    writer.write_all(self.convert_cow(&text[field.clone()])?.as_bytes())?;
+
+                    last = field.end;
+                }
+
+                writer.write_all(&record[last..])?;
+            }
+
+            position += record.len();
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Map the selected columns to their indices, or return `None` if every column is selected.
+fn column_indices(
+    columns: &[CsvColumn],
+    names: &[String],
+) -> Result<Option<Vec<usize>>, OpenCCError> {
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    columns
+        .iter()
+        .map(|column| match column {
+            CsvColumn::Index(index) => Ok(*index),
+            CsvColumn::Name(name) => {
+                names
+                    .iter()
+                    .position(|n| n == name)
+                    .ok_or_else(|| OpenCCError::InvalidDocument {
+                        format: "CSV",
+                        message: format!("the header row has no column named {:?}", name),
+                    })
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Split a record into the ranges of the contents of its fields, without their quotes and the line ending.
+///
+/// Return `None` if the record ends inside a quoted field, unless it is the last record of the input.
+fn split_record(record: &[u8], options: &CsvOptions, last: bool) -> Option<Vec<Range<usize>>> {
+    let end = record.len()
+        - if record.ends_with(b"\r\n") {
+            2
+        } else if record.ends_with(b"\n") {
+            1
+        } else {
+            0
+        };
+
+    let mut fields = Vec::new();
+    let mut i = 0;
+
+    loop {
+        if options.quote.is_some_and(|q| record.get(i) == Some(&q)) {
+            let quote = options.quote.unwrap_or_default();
+            let start = i + 1;
+
+            i = start;
+
+            loop {
+                match record.get(i) {
+                    None => {
+                        if !last {
+                            return None;
+                        }
+
+                        fields.push(start..record.len());
+
+                        return Some(fields);
+                    }
+                    Some(&b) if b == quote && record.get(i + 1) == Some(&quote) => i += 2,
+                    Some(&b) if b == quote => break,
+                    Some(_) => i += 1,
+                }
+            }
+
+            fields.push(start..i);
+
+            // Anything between the closing quote and the next delimiter is kept as it is.
+            i = record[i..end]
+                .iter()
+                .position(|&b| b == options.delimiter)
+                .map_or(end, |n| i + n);
+        } else {
+            let start = i;
+
+            i = record[i..end]
+                .iter()
+                .position(|&b| b == options.delimiter)
+                .map_or(end, |n| i + n);
+
+            fields.push(start..i);
+        }
+
+        if i >= end {
+            return Some(fields);
+        }
+
+        // Skip the delimiter.
+        i += 1;
+    }
+}
+
+/// Remove the quotes of a field content, and unescape the doubled quotes inside it.
+fn unquote(field: &str, quote: Option<u8>) -> String {
+    match quote {
+        Some(quote) => {
+            let quote = char::from(quote).to_string();
+
+            field.replace(&quote.repeat(2), &quote)
+        }
+        None => field.to_string(),
+    }
+}
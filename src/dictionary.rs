@@ -0,0 +1,645 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::{OpenCCError, json::Json};
+
+/// The header OpenCC writes before the marisa trie of an `ocd2` dictionary.
+const OCD2_HEADER: &[u8] = b"OPENCC_MARISA_0.2.5";
+/// The header of a marisa trie.
+const MARISA_HEADER: &[u8] = b"We love Marisa.\0";
+
+/// An OpenCC dictionary read into memory, with every key and its candidate values in the order of the
+/// dictionary. The first value is the one OpenCC converts to.
+#[derive(Debug, Default)]
+pub(crate) struct Dictionary {
+    pub(crate) entries: Vec<(String, Vec<String>)>,
+}
+
+impl Dictionary {
+    /// Read an `ocd2` dictionary.
+    pub(crate) fn from_ocd2(data: &[u8]) -> Result<Self, OpenCCError> {
+        let mut reader = Reader { data, position: 0 };
+
+        reader.expect(OCD2_HEADER)?;
+        reader.expect(MARISA_HEADER)?;
+
+        let trie = Trie::read(&mut reader)?;
+
+        let mut keys = Vec::new();
+
+        trie.for_each_key(|key, id| {
+            if keys.len() <= id {
+                keys.resize(id + 1, Vec::new());
+            }
+
+            keys[id] = key.to_vec();
+        })?;
+
+        let count = reader.u32()? as usize;
+        let values_length = reader.u32()? as usize;
+        let values = reader.bytes(values_length)?;
+
+        if count != keys.len() {
+            return Err(invalid_data("the number of values does not match the trie"));
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        let mut cursor = 0;
+
+        for key in keys {
+            let number = reader.u16()?;
+            let mut candidates = Vec::with_capacity(number as usize);
+
+            for _ in 0..number {
+                let length = reader.u16()? as usize;
+
+                // Every value is stored with its terminating NULL byte.
+                let value = values
+                    .get(cursor..cursor + length)
+                    .and_then(|value| value.strip_suffix(b"\0"))
+                    .ok_or_else(|| invalid_data("a value is out of range"))?;
+
+                candidates.push(utf8(value.to_vec())?);
+                cursor += length;
+            }
+
+            entries.push((utf8(key)?, candidates));
+        }
+
+        Ok(Dictionary { entries })
+    }
+
+    /// Read a text dictionary, with a key, a tab and space-separated values on each line.
+    pub(crate) fn from_text(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let (key, values) = line.split_once('\t')?;
+
+                Some((
+                    key.to_string(),
+                    values.split(' ').map(String::from).collect(),
+                ))
+            })
+            .collect();
+
+        Dictionary { entries }
+    }
+}
+
+/// Read the dictionaries referred to by a config, both for segmentation and conversion.
+///
+/// Only `ocd2` and `text` dictionaries next to the config or at absolute paths can be read. Any other
+/// dictionary is an error, so the caller never works with a part of the dictionaries.
+pub(crate) fn config_dictionaries(config_file_path: &Path) -> Result<Vec<Dictionary>, OpenCCError> {
+    let config = fs::read_to_string(config_file_path)?;
+    let config =
+        Json::parse(&config).ok_or_else(|| invalid_data("the config is not valid JSON"))?;
+
+    let config_dir = config_file_path.parent().unwrap_or(Path::new(""));
+
+    let mut files = Vec::new();
+
+    dictionary_files(&config, &mut files);
+
+    files
+        .into_iter()
+        .map(|(kind, file)| {
+            let path: PathBuf = config_dir.join(file);
+
+            match kind {
+                "ocd2" => Dictionary::from_ocd2(&fs::read(path)?),
+                "text" => Ok(Dictionary::from_text(&fs::read_to_string(path)?)),
+                _ => Err(invalid_data(format!(
+                    "{} dictionaries cannot be read",
+                    kind
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Get the characters that start a key which converts to something else, in any dictionary of a config.
+///
+/// Text without any of them is never changed by the config. Every match that changes the text starts
+/// with such a character, both in the first step of the conversion chain and in every later step, which
+/// sees the same text when the earlier steps changed nothing.
+pub(crate) fn key_starts(config_file_path: &Path) -> Result<HashSet<char>, OpenCCError> {
+    let mut starts = HashSet::new();
+
+    for dictionary in config_dictionaries(config_file_path)? {
+        for (key, values) in dictionary.entries {
+            if values.first().is_some_and(|value| *value != key)
+                && let Some(c) = key.chars().next()
+            {
+                starts.insert(c);
+            }
+        }
+    }
+
+    Ok(starts)
+}
+
+/// Collect the type and the file of every dictionary with a file.
+fn dictionary_files<'a>(value: &'a Json, files: &mut Vec<(&'a str, &'a str)>) {
+    match value {
+        Json::Object(members) => {
+            if let (Some(kind), Some(file)) = (
+                value.get("type").and_then(Json::as_str),
+                value.get("file").and_then(Json::as_str),
+            ) {
+                files.push((kind, file));
+            }
+
+            members
+                .iter()
+                .for_each(|(_, value)| dictionary_files(value, files));
+        }
+        Json::Array(values) => values
+            .iter()
+            .for_each(|value| dictionary_files(value, files)),
+        _ => (),
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> OpenCCError {
+    OpenCCError::Io(io::Error::new(ErrorKind::InvalidData, message.into()))
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String, OpenCCError> {
+    String::from_utf8(bytes).map_err(|_| invalid_data("a dictionary entry is not valid UTF-8"))
+}
+
+/// Reads the little-endian data that marisa and OpenCC write.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], OpenCCError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.saturating_add(length))
+            .ok_or_else(|| invalid_data("the dictionary is truncated"))?;
+
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    fn expect(&mut self, header: &[u8]) -> Result<(), OpenCCError> {
+        if self.bytes(header.len())? != header {
+            return Err(invalid_data("the dictionary is not an ocd2 file"));
+        }
+
+        Ok(())
+    }
+
+    fn u16(&mut self) -> Result<u16, OpenCCError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, OpenCCError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, OpenCCError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Read a marisa vector, which is its size in bytes, the elements and padding to 8 bytes.
+    fn vector(&mut self, element_size: usize) -> Result<&'a [u8], OpenCCError> {
+        let size =
+            usize::try_from(self.u64()?).map_err(|_| invalid_data("a vector is too large"))?;
+
+        if size % element_size != 0 {
+            return Err(invalid_data("a vector has a partial element"));
+        }
+
+        let bytes = self.bytes(size)?;
+
+        self.bytes((8 - size % 8) % 8)?;
+
+        Ok(bytes)
+    }
+}
+
+/// Get a bit of a little-endian bit stream.
+#[inline]
+fn bit(bytes: &[u8], i: usize) -> bool {
+    bytes[i / 8] & (1 << (i % 8)) != 0
+}
+
+/// A marisa bit vector with rank support. The select indexes in the file are skipped, because the trie
+/// structure is computed once instead.
+struct BitVector {
+    bytes: Vec<u8>,
+    /// The number of ones before each 64-bit block.
+    ranks: Vec<u32>,
+    len: usize,
+    ones: usize,
+}
+
+impl BitVector {
+    fn read(reader: &mut Reader) -> Result<Self, OpenCCError> {
+        let bytes = reader.vector(4)?.to_vec();
+        let len = reader.u32()? as usize;
+        let ones = reader.u32()? as usize;
+
+        // The rank and select indexes.
+        reader.vector(12)?;
+        reader.vector(4)?;
+        reader.vector(4)?;
+
+        if len > bytes.len() * 8 {
+            return Err(invalid_data("a bit vector is truncated"));
+        }
+
+        let mut ranks = Vec::with_capacity(bytes.len() / 8 + 1);
+        let mut rank = 0;
+
+        for block in bytes.chunks(8) {
+            ranks.push(rank);
+            rank += block.iter().map(|b| b.count_ones()).sum::<u32>();
+        }
+
+        Ok(BitVector {
+            bytes,
+            ranks,
+            len,
+            ones,
+        })
+    }
+
+    #[inline]
+    fn get(&self, i: usize) -> bool {
+        i < self.len && bit(&self.bytes, i)
+    }
+
+    /// Count the ones before `i`.
+    fn rank1(&self, i: usize) -> usize {
+        let block = i / 64;
+        let start = block * 8;
+
+        let mut rank = self.ranks[block] as usize;
+
+        rank += self.bytes[start..start + (i % 64) / 8]
+            .iter()
+            .map(|b| b.count_ones() as usize)
+            .sum::<usize>();
+
+        if !i.is_multiple_of(8) {
+            rank += (self.bytes[i / 8] & ((1 << (i % 8)) - 1)).count_ones() as usize;
+        }
+
+        rank
+    }
+}
+
+/// A marisa vector of integers of a fixed number of bits.
+struct FlatVector {
+    bytes: Vec<u8>,
+    value_size: usize,
+    len: usize,
+}
+
+impl FlatVector {
+    fn read(reader: &mut Reader) -> Result<Self, OpenCCError> {
+        let bytes = reader.vector(4)?.to_vec();
+        let value_size = reader.u32()? as usize;
+        let _mask = reader.u32()?;
+        let len = reader.u64()? as usize;
+
+        if value_size > 32 || len.saturating_mul(value_size) > bytes.len() * 8 {
+            return Err(invalid_data("a flat vector is truncated"));
+        }
+
+        Ok(FlatVector {
+            bytes,
+            value_size,
+            len,
+        })
+    }
+
+    fn get(&self, i: usize) -> Option<usize> {
+        if i >= self.len {
+            return None;
+        }
+
+        let start = i * self.value_size;
+
+        Some(
+            (0..self.value_size)
+                .filter(|j| bit(&self.bytes, start + j))
+                .map(|j| 1 << j)
+                .sum(),
+        )
+    }
+}
+
+/// The strings of the edges that do not fit in a trie.
+struct Tail {
+    buffer: Vec<u8>,
+    /// Marks the last byte of each string, unless the strings end with NULL bytes.
+    end_flags: BitVector,
+}
+
+impl Tail {
+    fn restore(&self, mut offset: usize, key: &mut Vec<u8>) -> Result<(), OpenCCError> {
+        let truncated = || invalid_data("a tail is out of range");
+
+        if self.end_flags.len == 0 {
+            let rest = self.buffer.get(offset..).ok_or_else(truncated)?;
+            let end = rest.iter().position(|&b| b == 0).ok_or_else(truncated)?;
+
+            key.extend_from_slice(&rest[..end]);
+        } else {
+            loop {
+                key.push(*self.buffer.get(offset).ok_or_else(truncated)?);
+
+                if self.end_flags.get(offset) {
+                    break;
+                }
+
+                offset += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A marisa LOUDS trie. The edges of a trie can be longer strings that are stored in the next trie,
+/// reversed, or in the tail of the last trie.
+struct Trie {
+    /// The first child of each node, and the number of nodes at the end.
+    first_children: Vec<u32>,
+    parents: Vec<u32>,
+    terminal_flags: BitVector,
+    link_flags: BitVector,
+    bases: Vec<u8>,
+    extras: FlatVector,
+    tail: Tail,
+    next: Option<Box<Trie>>,
+    level_one_nodes: usize,
+}
+
+impl Trie {
+    fn read(reader: &mut Reader) -> Result<Self, OpenCCError> {
+        let louds = BitVector::read(reader)?;
+        let terminal_flags = BitVector::read(reader)?;
+        let link_flags = BitVector::read(reader)?;
+        let bases = reader.vector(1)?.to_vec();
+        let extras = FlatVector::read(reader)?;
+        let tail = Tail {
+            buffer: reader.vector(1)?.to_vec(),
+            end_flags: BitVector::read(reader)?,
+        };
+
+        let next = if link_flags.ones != 0 && tail.buffer.is_empty() {
+            Some(Box::new(Trie::read(reader)?))
+        } else {
+            None
+        };
+
+        // The cache, which only speeds up lookups.
+        reader.vector(12)?;
+
+        let level_one_nodes = reader.u32()? as usize;
+        let _flags = reader.u32()?;
+
+        // Every node is a one, and every node ends the list of its children with a zero. The list of the
+        // root starts at 2, after a one and a zero for a super root.
+        let nodes = louds.ones;
+
+        if nodes == 0 || bases.len() < nodes {
+            return Err(invalid_data("the trie is truncated"));
+        }
+
+        let mut first_children = vec![nodes as u32; nodes + 1];
+        let mut parents = vec![0; nodes];
+        let mut node = 0;
+        let mut child = 1;
+
+        first_children[0] = 1;
+
+        for i in 2..louds.len {
+            if louds.get(i) {
+                if child >= nodes {
+                    return Err(invalid_data("the trie is malformed"));
+                }
+
+                parents[child] = node as u32;
+                child += 1;
+            } else {
+                node += 1;
+
+                if node > nodes {
+                    break;
+                }
+
+                first_children[node] = child as u32;
+            }
+        }
+
+        Ok(Trie {
+            first_children,
+            parents,
+            terminal_flags,
+            link_flags,
+            bases,
+            extras,
+            tail,
+            next,
+            level_one_nodes,
+        })
+    }
+
+    fn children(&self, node: usize) -> std::ops::Range<usize> {
+        self.first_children[node] as usize..self.first_children[node + 1] as usize
+    }
+
+    /// Append the label of the edge to `node`.
+    fn push_label(&self, node: usize, key: &mut Vec<u8>) -> Result<(), OpenCCError> {
+        if !self.link_flags.get(node) {
+            key.push(self.bases[node]);
+
+            return Ok(());
+        }
+
+        let extra = self
+            .extras
+            .get(self.link_flags.rank1(node))
+            .ok_or_else(|| invalid_data("a link is out of range"))?;
+        let link = self.bases[node] as usize | extra << 8;
+
+        match &self.next {
+            Some(next) => next.restore(link, key),
+            None => self.tail.restore(link, key),
+        }
+    }
+
+    /// Append the string of a node of a next trie, whose strings are reversed, by walking up to the root.
+    fn restore(&self, mut node: usize, key: &mut Vec<u8>) -> Result<(), OpenCCError> {
+        // Every step moves to a parent, which has a smaller number.
+        loop {
+            if node == 0 || node >= self.parents.len() {
+                return Err(invalid_data("a link is out of range"));
+            }
+
+            self.push_label(node, key)?;
+
+            if node <= self.level_one_nodes {
+                return Ok(());
+            }
+
+            node = self.parents[node] as usize;
+        }
+    }
+
+    /// Call `f` with every key of the trie and its ID.
+    fn for_each_key<F: FnMut(&[u8], usize)>(&self, mut f: F) -> Result<(), OpenCCError> {
+        let mut key = Vec::new();
+        let mut stack: Vec<(usize, usize)> =
+            self.children(0).rev().map(|child| (child, 0)).collect();
+
+        while let Some((node, length)) = stack.pop() {
+            key.truncate(length);
+            self.push_label(node, &mut key)?;
+
+            if self.terminal_flags.get(node) {
+                f(&key, self.terminal_flags.rank1(node));
+            }
+
+            stack.extend(self.children(node).rev().map(|child| (child, key.len())));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{Dictionary, config_dictionaries, key_starts};
+    use crate::{DefaultConfig, OpenCC};
+
+    fn opencc_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("opencc")
+    }
+
+    #[test]
+    fn ocd2_files() {
+        let mut count = 0;
+
+        for entry in fs::read_dir(opencc_dir()).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.extension().is_none_or(|e| e != "ocd2") {
+                continue;
+            }
+
+            let dictionary = Dictionary::from_ocd2(&fs::read(&path).unwrap()).unwrap();
+
+            assert!(!dictionary.entries.is_empty(), "{}", path.display());
+            assert!(
+                dictionary
+                    .entries
+                    .iter()
+                    .all(|(key, values)| !key.is_empty() && !values.is_empty()),
+                "{}",
+                path.display()
+            );
+
+            count += 1;
+        }
+
+        assert_eq!(16, count);
+
+        let characters =
+            Dictionary::from_ocd2(&fs::read(opencc_dir().join("STCharacters.ocd2")).unwrap())
+                .unwrap();
+
+        assert!(
+            characters
+                .entries
+                .iter()
+                .any(|(key, values)| key == "软" && values[0] == "軟")
+        );
+        assert!(
+            characters
+                .entries
+                .iter()
+                .any(|(key, values)| key == "干" && values.len() > 1)
+        );
+
+        let phrases =
+            Dictionary::from_ocd2(&fs::read(opencc_dir().join("TWPhrases.ocd2")).unwrap()).unwrap();
+
+        assert!(
+            phrases
+                .entries
+                .iter()
+                .any(|(key, values)| key == "軟件" && values[0] == "軟體")
+        );
+    }
+
+    #[test]
+    fn configs() {
+        for config in DefaultConfig::ALL {
+            let path = opencc_dir().join(config.get_file_name());
+            let name = config.get_file_name();
+
+            assert!(!config_dictionaries(&path).unwrap().is_empty(), "{}", name);
+
+            let starts = key_starts(&path).unwrap();
+
+            assert!(!starts.is_empty(), "{}", name);
+
+            if name.starts_with("s2") {
+                assert!(starts.contains(&'软') && starts.contains(&'网'), "{}", name);
+            }
+
+            if name.ends_with("2s.json") || name.ends_with("2sp.json") {
+                assert!(starts.contains(&'軟') && starts.contains(&'網'), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn text_dictionary() {
+        let dictionary = Dictionary::from_text("软件\t軟體 軟件\n\n网\t網\n");
+
+        assert_eq!(
+            vec![
+                (
+                    "软件".to_string(),
+                    vec!["軟體".to_string(), "軟件".to_string()]
+                ),
+                ("网".to_string(), vec!["網".to_string()]),
+            ],
+            dictionary.entries
+        );
+    }
+
+    #[test]
+    fn opencc_key_starts() {
+        for config in DefaultConfig::ALL {
+            let opencc = OpenCC::new(opencc_dir().join(config.get_file_name())).unwrap();
+
+            opencc.convert_cow("软件").unwrap();
+
+            assert!(
+                opencc.key_starts.get().is_some_and(Option::is_some),
+                "{}",
+                config.get_file_name()
+            );
+        }
+    }
+}
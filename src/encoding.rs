@@ -0,0 +1,266 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use encoding_rs::{BIG5, EncoderResult, Encoding, GB18030, GBK, UTF_8};
+
+use crate::{OpenCC, OpenCCError, Utf8Mode};
+
+/// Text encodings supported by [`OpenCC::convert_encoded`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LegacyEncoding {
+    /// UTF-8
+    Utf8,
+    /// GBK (CP936), which covers GB2312
+    Gbk,
+    /// GB18030
+    Gb18030,
+    /// Big5 (CP950) without the Hong Kong Supplementary Character Set
+    Big5,
+    /// Big5 with the Hong Kong Supplementary Character Set (HKSCS-2008)
+    Big5Hkscs,
+}
+
+impl LegacyEncoding {
+    /// Get the label of this encoding.
+    pub fn name(self) -> &'static str {
+        match self {
+            LegacyEncoding::Utf8 => "UTF-8",
+            LegacyEncoding::Gbk => "GBK",
+            LegacyEncoding::Gb18030 => "GB18030",
+            LegacyEncoding::Big5 => "Big5",
+            LegacyEncoding::Big5Hkscs => "Big5-HKSCS",
+        }
+    }
+
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            LegacyEncoding::Utf8 => UTF_8,
+            LegacyEncoding::Gbk => GBK,
+            LegacyEncoding::Gb18030 => GB18030,
+            // The WHATWG Big5 decoder already understands HKSCS, only the encoder needs to tell them apart.
+            LegacyEncoding::Big5 | LegacyEncoding::Big5Hkscs => BIG5,
+        }
+    }
+
+    /// Decode `input` without replacing malformed sequences.
+    pub(crate) fn decode(self, input: &[u8]) -> Result<String, OpenCCError> {
+        self.encoding()
+            .decode_without_bom_handling_and_without_replacement(input)
+            .map(|s| s.into_owned())
+            .ok_or(OpenCCError::InvalidInputEncoding(self.name()))
+    }
+}
+
+/// What to write when a character cannot be represented in the output encoding.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UnmappableFallback {
+    /// Write an HTML numeric character reference, like `&#128512;`.
+    NumericEntity,
+    /// Write the given string instead. It must be representable in the output encoding.
+    Replacement(String),
+    /// Stop with [`OpenCCError::Unmappable`].
+    Error,
+}
+
+/// A character that could not be represented in the output encoding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UnmappableCharacter {
+    /// The character.
+    pub character: char,
+    /// The byte offset of the character in the converted UTF-8 text.
+    pub offset: usize,
+}
+
+/// The result of [`OpenCC::convert_encoded`].
+#[derive(Debug, Clone, Default)]
+pub struct EncodedOutput {
+    /// The converted text in the output encoding.
+    pub bytes: Vec<u8>,
+    /// All characters that were written with the fallback.
+    pub unmappable: Vec<UnmappableCharacter>,
+}
+
+impl OpenCC {
+    /// Convert text stored in a legacy encoding, and write the result in another encoding.
+    ///
+    /// The input is decoded strictly, converted like [`OpenCC::convert_bytes`] (so NULL bytes are allowed),
+    /// and encoded into `output_encoding`. Characters that the output encoding cannot represent are
+    /// written with `fallback` and listed in [`EncodedOutput::unmappable`].
+    ///
+    /// # Errors
+    ///
+    /// If the input is malformed in `input_encoding`, if the C library conversion fails, or if `fallback`
+    /// is [`UnmappableFallback::Error`] and an unmappable character is found, an error is returned.
+    pub fn convert_encoded<B: AsRef<[u8]>>(
+        &self,
+        input: B,
+        input_encoding: LegacyEncoding,
+        output_encoding: LegacyEncoding,
+        fallback: &UnmappableFallback,
+    ) -> Result<EncodedOutput, OpenCCError> {
+        let decoded = input_encoding.decode(input.as_ref())?;
+
+        let converted = self.convert_bytes(decoded, Utf8Mode::Strict)?;
+        let converted = String::from_utf8(converted).map_err(|_| OpenCCError::InvalidUtf8)?;
+
+        encode(&converted, output_encoding, fallback)
+    }
+}
+
+fn encode(
+    input: &str,
+    encoding: LegacyEncoding,
+    fallback: &UnmappableFallback,
+) -> Result<EncodedOutput, OpenCCError> {
+    let mut result = EncodedOutput {
+        bytes: Vec::with_capacity(input.len()),
+        unmappable: Vec::new(),
+    };
+
+    let mut encoder = encoding.encoding().new_encoder();
+    let mut buffer = vec![0u8; 4096];
+    let mut position = 0;
+
+    loop {
+        let start = position;
+
+        let (status, read, written) =
+            encoder.encode_from_utf8_without_replacement(&input[position..], &mut buffer, true);
+
+        position += read;
+
+        let encoded_end = match status {
+            EncoderResult::Unmappable(c) => position - c.len_utf8(),
+            _ => position,
+        };
+
+        if encoding == LegacyEncoding::Big5 {
+            push_cp950(
+                &mut result,
+                &input[start..encoded_end],
+                start,
+                &buffer[..written],
+                fallback,
+            )?;
+        } else {
+            result.bytes.extend_from_slice(&buffer[..written]);
+        }
+
+        match status {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => continue,
+            EncoderResult::Unmappable(c) => {
+                if encoding == LegacyEncoding::Big5Hkscs
+                    && let Some(bytes) = hkscs_map().get(&c)
+                {
+                    result.bytes.extend_from_slice(bytes);
+                    continue;
+                }
+
+                push_fallback(&mut result, encoding, c, encoded_end, fallback)?;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Append the output of the WHATWG Big5 encoder for `input`, which starts at `offset`, writing the characters
+/// it encoded outside of CP950 (the ETEN extensions and HKSCS) with the fallback.
+fn push_cp950(
+    result: &mut EncodedOutput,
+    input: &str,
+    offset: usize,
+    mut bytes: &[u8],
+    fallback: &UnmappableFallback,
+) -> Result<(), OpenCCError> {
+    for (i, c) in input.char_indices() {
+        // The encoder writes ASCII as one byte and everything else as two bytes.
+        let length = if c.is_ascii() { 1 } else { 2 };
+        let (encoded, rest) = bytes.split_at(length);
+
+        if length == 1 || is_cp950(encoded) {
+            result.bytes.extend_from_slice(encoded);
+        } else {
+            push_fallback(result, LegacyEncoding::Big5, c, offset + i, fallback)?;
+        }
+
+        bytes = rest;
+    }
+
+    Ok(())
+}
+
+/// Write an unmappable character with the fallback, and record it.
+fn push_fallback(
+    result: &mut EncodedOutput,
+    encoding: LegacyEncoding,
+    c: char,
+    offset: usize,
+    fallback: &UnmappableFallback,
+) -> Result<(), OpenCCError> {
+    match fallback {
+        UnmappableFallback::NumericEntity => {
+            result
+                .bytes
+                .extend_from_slice(format!("&#{};", c as u32).as_bytes());
+        }
+        UnmappableFallback::Replacement(replacement) => {
+            result
+                .bytes
+                .extend(encoding.encoding().encode(replacement).0.iter().copied());
+        }
+        UnmappableFallback::Error => {
+            return Err(OpenCCError::Unmappable {
+                character: c,
+                offset,
+                encoding: encoding.name(),
+            });
+        }
+    }
+
+    result.unmappable.push(UnmappableCharacter {
+        character: c,
+        offset,
+    });
+
+    Ok(())
+}
+
+/// Whether a double-byte Big5 code is a part of CP950, which has neither the ETEN extensions at C6A1 to C8FE
+/// nor the HKSCS codes outside of the leads A1 to F9.
+pub(crate) fn is_cp950(bytes: &[u8]) -> bool {
+    match bytes {
+        [0xC6, 0xA1..=0xFE] | [0xC7..=0xC8, _] => false,
+        [0xA1..=0xF9, _] => true,
+        _ => false,
+    }
+}
+
+/// The HKSCS characters that the WHATWG Big5 encoder refuses to write, built by decoding the whole Big5 code space once.
+pub(crate) fn hkscs_map() -> &'static HashMap<char, [u8; 2]> {
+    static HKSCS: OnceLock<HashMap<char, [u8; 2]>> = OnceLock::new();
+
+    HKSCS.get_or_init(|| {
+        let mut map = HashMap::new();
+
+        for lead in 0x87..=0xFEu8 {
+            for trail in (0x40..=0x7Eu8).chain(0xA1..=0xFE) {
+                let bytes = [lead, trail];
+
+                let Some(decoded) =
+                    BIG5.decode_without_bom_handling_and_without_replacement(&bytes)
+                else {
+                    continue;
+                };
+
+                let mut chars = decoded.chars();
+
+                if let (Some(c), None) = (chars.next(), chars.next()) {
+                    map.entry(c).or_insert(bytes);
+                }
+            }
+        }
+
+        map
+    })
+}
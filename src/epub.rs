@@ -0,0 +1,165 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+use zip::{CompressionMethod, ZipArchive, ZipWriter, result::ZipError, write::SimpleFileOptions};
+
+use crate::{HtmlOptions, OpenCC, OpenCCError, file::TempFile, html::is_chinese_language_tag};
+
+const MIMETYPE: &str = "application/epub+zip";
+
+impl OpenCC {
+    /// Convert an EPUB e-book read from `reader` and write the result to `writer`.
+    ///
+    /// XHTML documents, including the navigation document, are converted with [`OpenCC::convert_html`]
+    /// and `options`. The titles of the NCX table of contents and the metadata of the OPF package
+    /// documents are converted as well, and the `dc:language` elements are set to `options.language_tag`
+    /// when they hold a Chinese language tag. Other entries are copied without being recompressed.
+    ///
+    /// The `mimetype` entry is always written first and uncompressed, as the EPUB specification requires.
+    ///
+    /// # Errors
+    ///
+    /// If reading or writing fails, if the input is not a ZIP archive, if a converted document is not
+    /// valid UTF-8, or if the C library conversion fails, an error is returned.
+    pub fn convert_epub<R: Read + Seek, W: Write + Seek>(
+        &self,
+        reader: R,
+        writer: W,
+        options: &HtmlOptions,
+    ) -> Result<W, OpenCCError> {
+        let mut archive = ZipArchive::new(reader).map_err(epub_error)?;
+        let mut zip = ZipWriter::new(writer);
+
+        zip.start_file(
+            "mimetype",
+            SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        )
+        .map_err(epub_error)?;
+        zip.write_all(MIMETYPE.as_bytes())?;
+
+        for index in 0..archive.len() {
+            let name = archive.name_for_index(index).unwrap_or_default().to_owned();
+
+            if name == "mimetype" {
+                continue;
+            }
+
+            let extension = Path::new(&name)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(str::to_ascii_lowercase);
+
+            if !matches!(
+                extension.as_deref(),
+                Some("xhtml" | "html" | "htm" | "ncx" | "opf")
+            ) {
+                zip.raw_copy_file(archive.by_index_raw(index).map_err(epub_error)?)
+                    .map_err(epub_error)?;
+
+                continue;
+            }
+
+            let mut file = archive.by_index(index).map_err(epub_error)?;
+
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+
+            let content = String::from_utf8(content)
+                .map_err(|e| OpenCCError::InputInvalidUtf8(e.utf8_error().valid_up_to()))?;
+
+            let mut converted = self.convert_html(content, options)?;
+
+            if extension.as_deref() == Some("opf")
+                && let Some(language_tag) = &options.language_tag
+            {
+                converted = replace_dc_language(&converted, language_tag);
+            }
+
+            let mut file_options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+            if let Some(last_modified) = file.last_modified() {
+                file_options = file_options.last_modified_time(last_modified);
+            }
+
+            drop(file);
+
+            zip.start_file(name, file_options).map_err(epub_error)?;
+            zip.write_all(converted.as_bytes())?;
+        }
+
+        zip.finish().map_err(epub_error)
+    }
+
+    /// Convert the EPUB e-book at `src` and write the result to `dst`.
+    ///
+    /// Like [`OpenCC::convert_file`], the output is written to a temporary file that replaces `dst`,
+    /// so `dst` is never left half-written.
+    pub fn convert_epub_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst: Q,
+        options: &HtmlOptions,
+    ) -> Result<(), OpenCCError> {
+        let dst = dst.as_ref();
+
+        let reader = BufReader::new(File::open(src)?);
+        let (temp, file) = TempFile::new(dst)?;
+
+        self.convert_epub(reader, BufWriter::new(file), options)?
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+
+        temp.persist(dst)?;
+
+        Ok(())
+    }
+}
+
+fn epub_error(error: ZipError) -> OpenCCError {
+    match error {
+        ZipError::Io(error) => error.into(),
+        error => OpenCCError::InvalidDocument {
+            format: "EPUB",
+            message: error.to_string(),
+        },
+    }
+}
+
+/// Replace the Chinese language tags in the `dc:language` elements of an OPF package document.
+fn replace_dc_language(opf: &str, language_tag: &str) -> String {
+    const OPEN: &str = "<dc:language";
+    const CLOSE: &str = "</dc:language>";
+
+    let mut output = String::with_capacity(opf.len());
+    let mut rest = opf;
+
+    while let Some(open) = rest.find(OPEN) {
+        let Some(content_start) = rest[open..].find('>').map(|n| open + n + 1) else {
+            break;
+        };
+        let Some(content_end) = rest[content_start..].find(CLOSE).map(|n| content_start + n) else {
+            break;
+        };
+
+        let content = &rest[content_start..content_end];
+
+        output.push_str(&rest[..content_start]);
+
+        if is_chinese_language_tag(content.trim()) {
+            output.push_str(language_tag);
+        } else {
+            output.push_str(content);
+        }
+
+        rest = &rest[content_end..];
+    }
+
+    output.push_str(rest);
+
+    output
+}
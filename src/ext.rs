@@ -0,0 +1,136 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{ConfigCache, DefaultConfig, OpenCCError};
+
+/// One-line conversions of strings with the process-wide converters of [`ConfigCache::global`].
+///
+/// The converters are built from the static dictionaries the first time each config is used, and shared by
+/// all threads afterwards.
+///
+/// ```rust
+/// use opencc_rust_windows::{ConvertExt, DefaultConfig};
+///
+/// assert_eq!("涼風有訊", "凉风有讯".to_traditional());
+/// assert_eq!("涼風有訊", "凉风有讯".to_string().convert_with(DefaultConfig::S2HK));
+/// assert_eq!("涼風有訊", format!("{}", "凉风有讯".display_with(DefaultConfig::S2TW)));
+/// ```
+pub trait ConvertExt {
+    /// Convert the string with `config`.
+    ///
+    /// # Errors
+    ///
+    /// If the config cannot be opened or the C library conversion fails, an error is returned.
+    fn try_convert_with(&self, config: DefaultConfig) -> Result<String, OpenCCError>;
+
+    /// Convert the string with `config`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn convert_with(&self, config: DefaultConfig) -> String {
+        self.try_convert_with(config).unwrap()
+    }
+
+    /// Convert Simplified Chinese to Traditional Chinese, with [`DefaultConfig::S2T`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_traditional(&self) -> String {
+        self.convert_with(DefaultConfig::S2T)
+    }
+
+    /// Convert Traditional Chinese to Simplified Chinese, with [`DefaultConfig::T2S`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_simplified(&self) -> String {
+        self.convert_with(DefaultConfig::T2S)
+    }
+
+    /// Convert Simplified Chinese to the Taiwan Standard, with [`DefaultConfig::S2TW`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_tw(&self) -> String {
+        self.convert_with(DefaultConfig::S2TW)
+    }
+
+    /// Convert Simplified Chinese to the Taiwan Standard with Taiwanese idiom, with [`DefaultConfig::S2TWP`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_tw_phrases(&self) -> String {
+        self.convert_with(DefaultConfig::S2TWP)
+    }
+
+    /// Convert Simplified Chinese to the Hong Kong Standard, with [`DefaultConfig::S2HK`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_hk(&self) -> String {
+        self.convert_with(DefaultConfig::S2HK)
+    }
+
+    /// Convert the Taiwan Standard to Simplified Chinese with Mainland Chinese idiom, with
+    /// [`DefaultConfig::TW2SP`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config cannot be opened or the C library conversion fails.
+    #[inline]
+    fn to_cn_phrases(&self) -> String {
+        self.convert_with(DefaultConfig::TW2SP)
+    }
+
+    /// Get an adapter that converts the string with `config` when it is formatted.
+    fn display_with(&self, config: DefaultConfig) -> ConvertedDisplay<'_>;
+}
+
+impl<T: AsRef<str> + ?Sized> ConvertExt for T {
+    #[inline]
+    fn try_convert_with(&self, config: DefaultConfig) -> Result<String, OpenCCError> {
+        Ok(ConfigCache::global()
+            .get(config)?
+            .convert_cow(self.as_ref())?
+            .into_owned())
+    }
+
+    #[inline]
+    fn display_with(&self, config: DefaultConfig) -> ConvertedDisplay<'_> {
+        ConvertedDisplay {
+            text: self.as_ref(),
+            config,
+        }
+    }
+}
+
+/// A [`Display`] adapter that converts a string only when it is formatted. It is created by
+/// [`ConvertExt::display_with`].
+///
+/// If the conversion fails, formatting returns [`fmt::Error`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertedDisplay<'a> {
+    text: &'a str,
+    config: DefaultConfig,
+}
+
+impl Display for ConvertedDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let opencc = ConfigCache::global()
+            .get(self.config)
+            .map_err(|_| fmt::Error)?;
+
+        f.pad(&opencc.convert_cow(self.text).map_err(|_| fmt::Error)?)
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Converter, OpenCC, OpenCCError, Utf8Mode};
+
+/// How many bytes are collected before a chunk is converted. Chunks are always extended to the end of a line.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl OpenCC {
+    /// Convert everything read from `reader` and write the result to `writer`.
+    ///
+    /// The input is converted in chunks of whole lines, so the memory usage only depends on the
+    /// length of the longest line. NULL bytes are allowed, but the input has to be valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// If reading or writing fails, if the input is not valid UTF-8, or if the C library conversion fails,
+    /// an error is returned.
+    pub fn convert_stream<R: BufRead, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<(), OpenCCError> {
+        convert_chunks(&mut reader, &mut writer, |chunk, writer| {
+            writer.write_all(&self.convert_bytes(chunk, Utf8Mode::Strict)?)?;
+
+            Ok(())
+        })
+    }
+
+    /// Convert the file at `src` and write the result to `dst`.
+    ///
+    /// The output is written to a temporary file next to `dst`, which then replaces `dst` by renaming,
+    /// so `dst` is never left half-written. The permissions of `src` are copied to `dst`.
+    ///
+    /// # Errors
+    ///
+    /// If any I/O operation fails, if the file is not valid UTF-8, or if the C library conversion fails,
+    /// an error is returned and `dst` is left untouched.
+    pub fn convert_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst: Q,
+    ) -> Result<(), OpenCCError> {
+        convert_file_with(self, src.as_ref(), dst.as_ref(), None, false)?;
+
+        Ok(())
+    }
+
+    /// Convert the file at `path` and replace it with the result.
+    ///
+    /// This works like [`OpenCC::convert_file`] with the same path as the source and the destination,
+    /// except that the file is not rewritten if the conversion does not change anything.
+    pub fn convert_file_in_place<P: AsRef<Path>>(&self, path: P) -> Result<(), OpenCCError> {
+        let path = path.as_ref();
+
+        convert_file_with(self, path, path, None, true)?;
+
+        Ok(())
+    }
+
+    /// Convert the file at `path` and replace it with the result, keeping a copy of the original file at `backup`.
+    ///
+    /// The backup is written before the original file is replaced, so one of them always holds the original content.
+    pub fn convert_file_in_place_with_backup<P: AsRef<Path>, B: AsRef<Path>>(
+        &self,
+        path: P,
+        backup: B,
+    ) -> Result<(), OpenCCError> {
+        let path = path.as_ref();
+
+        convert_file_with(self, path, path, Some(backup.as_ref()), true)?;
+
+        Ok(())
+    }
+}
+
+/// Read `reader` in chunks of whole lines, pass each chunk to `convert` together with `writer`, and flush
+/// `writer` at the end. An invalid UTF-8 position reported for a chunk is turned into a position in the whole input.
+pub(crate) fn convert_chunks<R: BufRead + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+    mut convert: impl FnMut(&[u8], &mut W) -> Result<(), OpenCCError>,
+) -> Result<(), OpenCCError> {
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    let mut position = 0;
+
+    loop {
+        chunk.clear();
+
+        while chunk.len() < CHUNK_SIZE {
+            if reader.read_until(b'\n', &mut chunk)? == 0 {
+                break;
+            }
+        }
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        convert(&chunk, writer).map_err(|e| match e {
+            OpenCCError::InputInvalidUtf8(n) => OpenCCError::InputInvalidUtf8(position + n),
+            e => e,
+        })?;
+
+        position += chunk.len();
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Check whether converting the file at `path` would change it, without writing anything.
+#[cfg(feature = "tree")]
+pub(crate) fn file_would_change<C: Converter + ?Sized>(
+    converter: &C,
+    path: &Path,
+) -> Result<bool, OpenCCError> {
+    let mut writer = ComparingWriter::new(io::sink(), BufReader::new(File::open(path)?));
+
+    converter.convert_stream(&mut BufReader::new(File::open(path)?), &mut writer)?;
+
+    Ok(writer.finish()?.1)
+}
+
+/// Convert `src` into `dst` and return whether the content changed. If `skip_unchanged` is set and
+/// nothing changed, `dst` and `backup` are not touched.
+pub(crate) fn convert_file_with<C: Converter + ?Sized>(
+    converter: &C,
+    src: &Path,
+    dst: &Path,
+    backup: Option<&Path>,
+    skip_unchanged: bool,
+) -> Result<bool, OpenCCError> {
+    let source = File::open(src)?;
+    let permissions = source.metadata()?.permissions();
+
+    // Replace the target of a symbolic link instead of the link itself.
+    let dst = if dst.is_symlink() {
+        fs::canonicalize(dst)?
+    } else {
+        dst.to_path_buf()
+    };
+
+    let (temp, file) = TempFile::new(&dst)?;
+
+    let mut writer = ComparingWriter::new(BufWriter::new(file), BufReader::new(File::open(src)?));
+
+    converter.convert_stream(&mut BufReader::new(source), &mut writer)?;
+
+    let (writer, changed) = writer.finish()?;
+
+    if !changed && skip_unchanged {
+        return Ok(false);
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+
+    fs::set_permissions(&temp.path, permissions)?;
+
+    if let Some(backup) = backup {
+        fs::copy(src, backup)?;
+    }
+
+    temp.persist(&dst)?;
+
+    Ok(changed)
+}
+
+/// Replace the content of an existing file through a temporary file, keeping its permissions.
+#[cfg(feature = "tree")]
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let permissions = fs::metadata(path)?.permissions();
+
+    let (temp, mut file) = TempFile::new(path)?;
+
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::set_permissions(&temp.path, permissions)?;
+
+    temp.persist(path)
+}
+
+/// A writer that compares everything written to it with the content read from `original`.
+struct ComparingWriter<W, R> {
+    inner: W,
+    original: R,
+    buffer: Vec<u8>,
+    changed: bool,
+}
+
+impl<W: Write, R: Read> ComparingWriter<W, R> {
+    fn new(inner: W, original: R) -> Self {
+        ComparingWriter {
+            inner,
+            original,
+            buffer: Vec::new(),
+            changed: false,
+        }
+    }
+
+    /// Return the inner writer, and whether the written content differs from the original.
+    fn finish(mut self) -> io::Result<(W, bool)> {
+        if !self.changed {
+            self.changed = self.original.read(&mut [0u8; 1])? != 0;
+        }
+
+        Ok((self.inner, self.changed))
+    }
+}
+
+impl<W: Write, R: Read> Write for ComparingWriter<W, R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+
+        if !self.changed {
+            self.buffer.resize(n, 0);
+
+            match self.original.read_exact(&mut self.buffer) {
+                Ok(()) => self.changed = self.buffer[..] != buf[..n],
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => self.changed = true,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A temporary file in the same directory as its destination, removed on drop unless it is persisted.
+pub(crate) struct TempFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl TempFile {
+    pub(crate) fn new(dst: &Path) -> io::Result<(Self, File)> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let directory = match dst.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        let file_name = dst
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid file path"))?;
+
+        let path = directory.join(format!(
+            ".{}.{}.{}.tmp",
+            file_name.to_string_lossy(),
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        TempFile::create(path)
+    }
+
+    /// Create a file at `path` that is removed when it is dropped. It fails if anything exists at `path`, so
+    /// a symbolic link planted there is never followed.
+    pub(crate) fn create(path: PathBuf) -> io::Result<(Self, File)> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        Ok((
+            TempFile {
+                path,
+                persisted: false,
+            },
+            file,
+        ))
+    }
+
+    #[cfg(feature = "rules")]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn persist(mut self, dst: &Path) -> io::Result<()> {
+        fs::rename(&self.path, dst)?;
+        self.persisted = true;
+
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
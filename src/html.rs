@@ -0,0 +1,426 @@
+use std::ops::Range;
+
+use crate::{DefaultConfig, OpenCC, OpenCCError};
+
+/// Elements that never have content or an end tag in HTML.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is not markup, so it is read up to their end tag as it is.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Options for [`OpenCC::convert_html`].
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// The attributes whose values are converted. The default is `title`, `alt` and `placeholder`.
+    pub attributes: Vec<String>,
+    /// The `name` or `property` values of the `meta` elements whose `content` is converted. The default is
+    /// `description`, `keywords`, `og:title` and `og:description`.
+    pub meta_names: Vec<String>,
+    /// The elements whose content and attributes are left as they are. The default is `script`, `style`
+    /// and `code`. Elements with `translate="no"` are always left as they are.
+    pub skip_elements: Vec<String>,
+    /// The language tag that replaces the Chinese `lang` and `xml:lang` values, like `zh-CN`. The default
+    /// is `None`, which leaves them as they are.
+    pub language_tag: Option<String>,
+}
+
+impl HtmlOptions {
+    /// Create the default options with the `lang` values replaced by the target language tag of `config`.
+    pub fn for_config(config: DefaultConfig) -> Self {
+        HtmlOptions {
+            language_tag: config.target_language_tag().map(String::from),
+            ..HtmlOptions::default()
+        }
+    }
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            attributes: vec!["title".into(), "alt".into(), "placeholder".into()],
+            meta_names: vec![
+                "description".into(),
+                "keywords".into(),
+                "og:title".into(),
+                "og:description".into(),
+            ],
+            skip_elements: vec!["script".into(), "style".into(), "code".into()],
+            language_tag: None,
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert the text of an HTML or XML document.
+    ///
+    /// Only text nodes, CDATA sections and the attributes chosen in `options` are converted. The content
+    /// of skipped elements and of elements with `translate="no"` is left alone, as are comments, doctypes
+    /// and processing instructions. Everything that is not converted is copied byte for byte, so the
+    /// layout of the markup does not change.
+    ///
+    /// # Errors
+    ///
+    /// If the C library conversion fails, an error is returned.
+    pub fn convert_html<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &HtmlOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for (range, edit) in html_edits(input, options) {
+            output.push_str(&input[last..range.start]);
+
+            match edit {
+                Edit::Convert { .. } => output.push_str(&self.convert_cow(&input[range.clone()])?),
+                Edit::Language => output.push_str(
+                    options
+                        .language_tag
+                        .as_deref()
+                        .unwrap_or(&input[range.clone()]),
+                ),
+            }
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
+
+pub(crate) enum Edit {
+    /// Convert the text. The range is the value of the `lang` attribute in scope, if there is one.
+    Convert { lang: Option<Range<usize>> },
+    /// A Chinese `lang` or `xml:lang` value.
+    Language,
+}
+
+pub(crate) struct Attribute {
+    pub(crate) name: Range<usize>,
+    /// The value without its quotes.
+    pub(crate) value: Option<Range<usize>>,
+}
+
+pub(crate) struct StartTag {
+    /// The name in lowercase.
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<Attribute>,
+    pub(crate) self_closing: bool,
+    pub(crate) end: usize,
+}
+
+/// An open element with a `lang` attribute.
+struct LanguageScope {
+    name: String,
+    lang: Range<usize>,
+    /// The number of open elements with the same name inside this one.
+    nested: usize,
+}
+
+/// Collect the edits of a document, in the order of their ranges.
+pub(crate) fn html_edits(input: &str, options: &HtmlOptions) -> Vec<(Range<usize>, Edit)> {
+    let mut edits = Vec::new();
+    // The open elements inside a skipped element, starting with the skipped element itself.
+    let mut skipped: Vec<String> = Vec::new();
+    let mut languages: Vec<LanguageScope> = Vec::new();
+    let mut i = 0;
+
+    while i < input.len() {
+        let lang = languages.last().map(|scope| scope.lang.clone());
+
+        let Some(lt) = input[i..].find('<') else {
+            if skipped.is_empty() {
+                edits.push((i..input.len(), Edit::Convert { lang }));
+            }
+
+            break;
+        };
+
+        let start = i + lt;
+
+        if skipped.is_empty() && start > i {
+            edits.push((i..start, Edit::Convert { lang: lang.clone() }));
+        }
+
+        let rest = &input[start..];
+
+        if rest.starts_with("<!--") {
+            i = find_end(input, start + 4, "-->");
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let content_start = start + 9;
+            let content_end = cdata.find("]]>").map_or(input.len(), |n| content_start + n);
+
+            if skipped.is_empty() {
+                edits.push((content_start..content_end, Edit::Convert { lang }));
+            }
+
+            i = find_end(input, content_end, "]]>");
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            i = find_end(input, start + 2, ">");
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let name = end_tag
+                .split(|c: char| c.is_ascii_whitespace() || c == '>')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+
+            if let Some(position) = skipped.iter().rposition(|n| *n == name) {
+                skipped.truncate(position);
+            }
+
+            if let Some(position) = languages.iter().rposition(|scope| scope.name == name) {
+                if languages[position].nested > 0 {
+                    languages[position].nested -= 1;
+                } else {
+                    languages.truncate(position);
+                }
+            }
+
+            i = find_end(input, start + 2, ">");
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let tag = parse_start_tag(input, start);
+
+            let skip = !skipped.is_empty()
+                || options
+                    .skip_elements
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(&tag.name))
+                || tag.attributes.iter().any(|attribute| {
+                    input[attribute.name.clone()].eq_ignore_ascii_case("translate")
+                        && attribute
+                            .value
+                            .as_ref()
+                            .is_some_and(|v| input[v.clone()].eq_ignore_ascii_case("no"))
+                });
+
+            let own_lang = tag
+                .attributes
+                .iter()
+                .find(|attribute| {
+                    let name = &input[attribute.name.clone()];
+
+                    name.eq_ignore_ascii_case("lang") || name.eq_ignore_ascii_case("xml:lang")
+                })
+                .and_then(|attribute| attribute.value.clone());
+
+            if !skip {
+                let lang = own_lang.clone().or(lang);
+
+                attribute_edits(input, &tag, options, lang, &mut edits);
+            }
+
+            i = tag.end;
+
+            if !tag.self_closing && !VOID_ELEMENTS.contains(&tag.name.as_str()) {
+                if let Some(lang) = own_lang {
+                    languages.push(LanguageScope {
+                        name: tag.name.clone(),
+                        lang,
+                        nested: 0,
+                    });
+                } else if let Some(scope) = languages.iter_mut().rev().find(|s| s.name == tag.name)
+                {
+                    scope.nested += 1;
+                }
+
+                if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+                    // The content is never converted, the end tag is handled by the next iteration.
+                    i = find_ignore_ascii_case(&input[i..], &format!("</{}", tag.name))
+                        .map_or(input.len(), |n| i + n);
+                }
+
+                if skip {
+                    skipped.push(tag.name);
+                }
+            }
+        } else {
+            // A `<` that does not start any markup is text.
+            if skipped.is_empty() {
+                edits.push((start..start + 1, Edit::Convert { lang }));
+            }
+
+            i = start + 1;
+        }
+    }
+
+    edits
+}
+
+/// Collect the edits of the attributes of a start tag.
+fn attribute_edits(
+    input: &str,
+    tag: &StartTag,
+    options: &HtmlOptions,
+    lang: Option<Range<usize>>,
+    edits: &mut Vec<(Range<usize>, Edit)>,
+) {
+    let meta_content = tag.name == "meta"
+        && tag.attributes.iter().any(|attribute| {
+            let name = &input[attribute.name.clone()];
+
+            (name.eq_ignore_ascii_case("name") || name.eq_ignore_ascii_case("property"))
+                && attribute.value.as_ref().is_some_and(|v| {
+                    options
+                        .meta_names
+                        .iter()
+                        .any(|n| n.eq_ignore_ascii_case(&input[v.clone()]))
+                })
+        });
+
+    for attribute in &tag.attributes {
+        let Some(value) = &attribute.value else {
+            continue;
+        };
+
+        let name = &input[attribute.name.clone()];
+
+        if name.eq_ignore_ascii_case("lang") || name.eq_ignore_ascii_case("xml:lang") {
+            if is_chinese_language_tag(&input[value.clone()]) {
+                edits.push((value.clone(), Edit::Language));
+            }
+        } else if options
+            .attributes
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(name))
+            || (meta_content && name.eq_ignore_ascii_case("content"))
+        {
+            edits.push((value.clone(), Edit::Convert { lang: lang.clone() }));
+        }
+    }
+}
+
+/// Parse the start tag beginning at `start`, which points to its `<`.
+pub(crate) fn parse_start_tag(input: &str, start: usize) -> StartTag {
+    let bytes = input.as_bytes();
+
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || matches!(b, b'/' | b'>');
+    let skip_whitespace = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        i
+    };
+
+    let mut i = start + 1;
+
+    while i < bytes.len() && !is_name_end(bytes[i]) {
+        i += 1;
+    }
+
+    let name = input[start + 1..i].to_ascii_lowercase();
+
+    let mut attributes = Vec::new();
+
+    loop {
+        i = skip_whitespace(i);
+
+        match bytes.get(i) {
+            None => {
+                return StartTag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                    end: i,
+                };
+            }
+            Some(b'>') => {
+                return StartTag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                    end: i + 1,
+                };
+            }
+            Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                return StartTag {
+                    name,
+                    attributes,
+                    self_closing: true,
+                    end: i + 2,
+                };
+            }
+            Some(b'/') => {
+                i += 1;
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let name_start = i;
+
+        while i < bytes.len() && !is_name_end(bytes[i]) && bytes[i] != b'=' {
+            i += 1;
+        }
+
+        let attribute_name = name_start..i;
+
+        i = skip_whitespace(i);
+
+        if bytes.get(i) != Some(&b'=') {
+            attributes.push(Attribute {
+                name: attribute_name,
+                value: None,
+            });
+
+            continue;
+        }
+
+        i = skip_whitespace(i + 1);
+
+        let value = match bytes.get(i) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let value_start = i + 1;
+                let value_end = input[value_start..]
+                    .find(quote as char)
+                    .map_or(input.len(), |n| value_start + n);
+
+                i = (value_end + 1).min(input.len());
+
+                value_start..value_end
+            }
+            _ => {
+                let value_start = i;
+
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                    i += 1;
+                }
+
+                value_start..i
+            }
+        };
+
+        attributes.push(Attribute {
+            name: attribute_name,
+            value: Some(value),
+        });
+    }
+}
+
+/// Return the index after the first `pattern` at or after `from`, or the end of the input.
+pub(crate) fn find_end(input: &str, from: usize, pattern: &str) -> usize {
+    input[from..]
+        .find(pattern)
+        .map_or(input.len(), |n| from + n + pattern.len())
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Whether a language tag is `zh` or starts with `zh-`.
+pub(crate) fn is_chinese_language_tag(tag: &str) -> bool {
+    tag.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("zh"))
+        && matches!(tag.as_bytes().get(2), None | Some(b'-' | b'_'))
+}
@@ -0,0 +1,328 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+/// A JSON value, for reading and writing OpenCC configs.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    /// A number, kept as it was written.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// The members of an object, in the order of the source.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse a JSON document. Returns `None` if it is not valid JSON.
+    pub(crate) fn parse(source: &str) -> Option<Json> {
+        let mut parser = Parser {
+            source: source.as_bytes(),
+            position: 0,
+        };
+
+        let value = parser.value()?;
+
+        parser.skip_whitespace();
+
+        (parser.position == source.len()).then_some(value)
+    }
+
+    /// Get a member of an object.
+    pub(crate) fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Replace a member of an object, or add it if it is missing. Other values are left unchanged.
+    #[cfg(feature = "rules")]
+    pub(crate) fn set(&mut self, name: &str, value: Json) {
+        if let Json::Object(members) = self {
+            match members.iter_mut().find(|(n, _)| n == name) {
+                Some((_, old)) => *old = value,
+                None => members.push((name.to_string(), value)),
+            }
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "rules")]
+    pub(crate) fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// Write the value as compact JSON.
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => f.write_str(n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                f.write_char('[')?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    write!(f, "{}", value)?;
+                }
+
+                f.write_char(']')
+            }
+            Json::Object(members) => {
+                f.write_char('{')?;
+
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+struct Parser<'a> {
+    source: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.get(self.position).copied()
+    }
+
+    fn eat(&mut self, literal: &[u8]) -> bool {
+        if self.source[self.position..].starts_with(literal) {
+            self.position += literal.len();
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+
+        match self.peek()? {
+            b'{' => {
+                self.position += 1;
+
+                let mut members = Vec::new();
+
+                self.skip_whitespace();
+
+                if self.eat(b"}") {
+                    return Some(Json::Object(members));
+                }
+
+                loop {
+                    self.skip_whitespace();
+
+                    let name = self.string()?;
+
+                    self.skip_whitespace();
+
+                    if !self.eat(b":") {
+                        return None;
+                    }
+
+                    members.push((name, self.value()?));
+
+                    self.skip_whitespace();
+
+                    if self.eat(b"}") {
+                        return Some(Json::Object(members));
+                    }
+
+                    if !self.eat(b",") {
+                        return None;
+                    }
+                }
+            }
+            b'[' => {
+                self.position += 1;
+
+                let mut values = Vec::new();
+
+                self.skip_whitespace();
+
+                if self.eat(b"]") {
+                    return Some(Json::Array(values));
+                }
+
+                loop {
+                    values.push(self.value()?);
+
+                    self.skip_whitespace();
+
+                    if self.eat(b"]") {
+                        return Some(Json::Array(values));
+                    }
+
+                    if !self.eat(b",") {
+                        return None;
+                    }
+                }
+            }
+            b'"' => self.string().map(Json::String),
+            b't' => self.eat(b"true").then_some(Json::Bool(true)),
+            b'f' => self.eat(b"false").then_some(Json::Bool(false)),
+            b'n' => self.eat(b"null").then_some(Json::Null),
+            _ => {
+                let start = self.position;
+
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                ) {
+                    self.position += 1;
+                }
+
+                let number = std::str::from_utf8(&self.source[start..self.position]).ok()?;
+
+                (!number.is_empty()).then(|| Json::Number(number.to_string()))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if !self.eat(b"\"") {
+            return None;
+        }
+
+        let mut output = Vec::new();
+
+        loop {
+            let b = self.peek()?;
+
+            self.position += 1;
+
+            match b {
+                b'"' => return String::from_utf8(output).ok(),
+                b'\\' => {
+                    let escaped = self.peek()?;
+
+                    self.position += 1;
+
+                    match escaped {
+                        b'"' | b'\\' | b'/' => output.push(escaped),
+                        b'b' => output.push(0x08),
+                        b'f' => output.push(0x0C),
+                        b'n' => output.push(b'\n'),
+                        b'r' => output.push(b'\r'),
+                        b't' => output.push(b'\t'),
+                        b'u' => {
+                            let mut code = self.hex4()?;
+
+                            if (0xD800..0xDC00).contains(&code) && self.eat(b"\\u") {
+                                let low = self.hex4()?;
+
+                                code =
+                                    0x10000 + ((code - 0xD800) << 10) + low.checked_sub(0xDC00)?;
+                            }
+
+                            output.extend_from_slice(
+                                char::from_u32(code)?.encode_utf8(&mut [0; 4]).as_bytes(),
+                            );
+                        }
+                        _ => return None,
+                    }
+                }
+                _ => output.push(b),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.source.get(self.position..self.position + 4)?;
+
+        self.position += 4;
+
+        u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    #[test]
+    fn parse() {
+        let json = Json::parse(
+            r#" {"name": "S2T", "n": -1.5e3, "ok": [true, false, null], "s": "软\"\n"} "#,
+        )
+        .unwrap();
+
+        assert_eq!(Some("S2T"), json.get("name").and_then(Json::as_str));
+        assert_eq!(Some(&Json::Number("-1.5e3".to_string())), json.get("n"));
+        assert_eq!(
+            Some(&Json::Array(vec![
+                Json::Bool(true),
+                Json::Bool(false),
+                Json::Null
+            ])),
+            json.get("ok")
+        );
+        assert_eq!(Some("软\"\n"), json.get("s").and_then(Json::as_str));
+
+        for invalid in ["", "{", "[1,]", "{\"a\" 1}", "\"a", "tru", "{} {}"] {
+            assert_eq!(None, Json::parse(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn write() {
+        let source = r#"{"name":"软件\"\\\n\u0001","list":[1,-2.5,true,null,{}],"empty":[]}"#;
+
+        let json = Json::parse(source).unwrap();
+
+        assert_eq!(source, json.to_string());
+        assert_eq!(Some(json.clone()), Json::parse(&json.to_string()));
+    }
+}
@@ -84,21 +84,104 @@ assert_eq!("凉风有讯", &opencc.convert("涼風有訊").unwrap());
 ```
 */
 
+#[cfg(feature = "static-dictionaries")]
+use std::env;
 #[cfg(feature = "static-dictionaries")]
 use std::error::Error;
 #[cfg(feature = "static-dictionaries")]
-use std::fs::{self, File};
+use std::fs;
 #[cfg(feature = "static-dictionaries")]
-use std::io::Write;
-use std::sync::Mutex;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
 use std::{
+    borrow::Cow,
+    collections::HashSet,
     ffi::{CStr, CString},
-    path::Path,
+    path::{self, Path, PathBuf},
+    str::FromStr,
 };
 
 use libc::{c_char, c_int, c_void, size_t};
 use thiserror::Error;
 
+#[cfg(feature = "cache")]
+mod cached;
+#[cfg(feature = "catalog")]
+mod catalog;
+mod config_cache;
+mod converter;
+mod convertible;
+#[cfg(feature = "encoding")]
+mod coverage;
+#[cfg(feature = "csv")]
+mod csv;
+mod dictionary;
+#[cfg(feature = "encoding")]
+mod encoding;
+#[cfg(feature = "epub")]
+mod epub;
+#[cfg(feature = "static-dictionaries")]
+mod ext;
+mod file;
+#[cfg(feature = "html")]
+mod html;
+mod json;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod mixed;
+mod pipeline;
+mod protect;
+#[cfg(feature = "rules")]
+mod rules;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "source")]
+mod source;
+#[cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+mod structured;
+#[cfg(feature = "subtitle")]
+mod subtitle;
+#[cfg(feature = "tree")]
+mod tree;
+#[cfg(any(feature = "catalog", feature = "source"))]
+mod utf8;
+
+#[cfg(feature = "cache")]
+pub use cached::{CacheStats, CachedOpenCC};
+#[cfg(feature = "catalog")]
+pub use catalog::{CatalogFormat, CatalogOptions};
+pub use config_cache::ConfigCache;
+pub use converter::{Converter, MappingConverter, PassthroughConverter};
+pub use convertible::Convertible;
+#[cfg(feature = "encoding")]
+pub use coverage::{CoverageReport, OutOfSetCharacter, Repertoire, check_coverage};
+#[cfg(feature = "csv")]
+pub use csv::{CsvColumn, CsvOptions};
+#[cfg(feature = "encoding")]
+pub use encoding::{EncodedOutput, LegacyEncoding, UnmappableCharacter, UnmappableFallback};
+#[cfg(feature = "static-dictionaries")]
+pub use ext::{ConvertExt, ConvertedDisplay};
+#[cfg(feature = "html")]
+pub use html::HtmlOptions;
+#[cfg(feature = "markdown")]
+pub use markdown::MarkdownOptions;
+#[cfg(feature = "derive")]
+pub use opencc_rust_windows_derive::Convertible;
+pub use pipeline::{Normalization, Pipeline, StageTiming};
+pub use protect::ConvertOptions;
+#[cfg(feature = "rules")]
+pub use rules::RuleSet;
+#[cfg(feature = "source")]
+pub use source::{SourceLanguage, SourceOptions};
+#[cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+pub use structured::StructuredOptions;
+#[cfg(feature = "subtitle")]
+pub use subtitle::{SubtitleFormat, SubtitleOptions};
+#[cfg(feature = "tree")]
+pub use tree::{
+    FileStatus, NameCollision, TreeEntry, TreeOptions, TreeOutput, TreeSummary, convert_tree,
+};
+
 unsafe extern "C" {
     pub fn opencc_open(config_file_path: *const c_char) -> *mut c_void;
     pub fn opencc_close(opencc: *mut c_void) -> c_int;
@@ -118,7 +201,7 @@ unsafe extern "C" {
 }
 
 /// Default configs.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum DefaultConfig {
     /// Traditional Chinese (Hong Kong Standard) to Simplified Chinese
     HK2S,
@@ -151,6 +234,24 @@ pub enum DefaultConfig {
 }
 
 impl DefaultConfig {
+    /// All default configs.
+    pub const ALL: [DefaultConfig; 14] = [
+        DefaultConfig::HK2S,
+        DefaultConfig::HK2T,
+        DefaultConfig::JP2T,
+        DefaultConfig::S2HK,
+        DefaultConfig::S2T,
+        DefaultConfig::S2TW,
+        DefaultConfig::S2TWP,
+        DefaultConfig::T2HK,
+        DefaultConfig::T2JP,
+        DefaultConfig::T2S,
+        DefaultConfig::T2TW,
+        DefaultConfig::TW2S,
+        DefaultConfig::TW2SP,
+        DefaultConfig::TW2T,
+    ];
+
     /// Get the file name for this default config.
     pub fn get_file_name(self) -> &'static str {
         match self {
@@ -170,6 +271,38 @@ impl DefaultConfig {
             DefaultConfig::TW2T => "tw2t.json",
         }
     }
+
+    /// Get the BCP 47 language tag of the text produced by this default config, like `zh-TW` for `S2TWP`.
+    ///
+    /// The Japanese configs return `None`, because their output is still Japanese text.
+    pub fn target_language_tag(self) -> Option<&'static str> {
+        match self {
+            DefaultConfig::S2T | DefaultConfig::HK2T | DefaultConfig::TW2T => Some("zh-Hant"),
+            DefaultConfig::S2TW | DefaultConfig::S2TWP | DefaultConfig::T2TW => Some("zh-TW"),
+            DefaultConfig::S2HK | DefaultConfig::T2HK => Some("zh-HK"),
+            DefaultConfig::T2S | DefaultConfig::HK2S | DefaultConfig::TW2S => Some("zh-Hans"),
+            DefaultConfig::TW2SP => Some("zh-CN"),
+            DefaultConfig::JP2T | DefaultConfig::T2JP => None,
+        }
+    }
+}
+
+impl FromStr for DefaultConfig {
+    type Err = OpenCCError;
+
+    /// Parse a config name like `s2twp`, `S2TWP` or `s2twp.json`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.strip_suffix(".json").unwrap_or(s);
+
+        DefaultConfig::ALL
+            .into_iter()
+            .find(|c| {
+                c.get_file_name()
+                    .strip_suffix(".json")
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| OpenCCError::UnknownConfig(s.to_string()))
+    }
 }
 
 impl AsRef<Path> for DefaultConfig {
@@ -185,8 +318,15 @@ impl AsRef<str> for DefaultConfig {
 }
 
 /// Represents all errors that may occur
+///
+/// Every variant exists whatever features are enabled, and new variants may be added in later versions.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum OpenCCError {
+    /// Occurs when a name cannot be parsed as a [`DefaultConfig`].
+    #[error("Unknown default config: {0}")]
+    UnknownConfig(String),
+
     /// Occurs when the supplied path cannot be converted to a C string, usually because it contains internal NULL characters.
     #[error("The configuration file path contains invalid characters")]
     InvalidConfigPath,
@@ -209,11 +349,58 @@ pub enum OpenCCError {
     /// Occurs when the OpenCC C library returns an illegal UTF-8 byte sequence.
     #[error("OpenCC returned an invalid UTF-8 sequence")]
     InvalidUtf8,
+
+    /// Occurs when [`Utf8Mode::Strict`] is used and the input is not valid UTF-8.
+    /// The usize is the length of the valid prefix of the input, in bytes.
+    #[error("The input is not valid UTF-8 after byte {0}")]
+    InputInvalidUtf8(usize),
+
+    /// Occurs when reading or writing a file or a stream fails.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Occurs when an include or exclude glob cannot be parsed.
+    /// The String contains the details from the glob parser.
+    #[error("Invalid glob: {0}")]
+    InvalidGlob(String),
+
+    /// Occurs when the input is malformed in the encoding it is declared to be in.
+    /// The str is the name of the encoding.
+    #[error("The input is not valid {0}")]
+    InvalidInputEncoding(&'static str),
+
+    /// Occurs when `UnmappableFallback::Error` is used and a character cannot be represented in the output encoding.
+    #[error("The character {character:?} at byte {offset} cannot be represented in {encoding}")]
+    Unmappable {
+        character: char,
+        offset: usize,
+        encoding: &'static str,
+    },
+
+    /// Occurs when a document cannot be parsed in the format it is converted as.
+    /// The format is a name like `JSON`, and the message contains the details from the parser.
+    #[error("Invalid {format}: {message}")]
+    InvalidDocument {
+        format: &'static str,
+        message: String,
+    },
+}
+
+/// How byte-level conversion treats data that is not valid UTF-8.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Utf8Mode {
+    /// Reject invalid UTF-8, both in the input and in the output of the OpenCC C library.
+    Strict,
+    /// Replace invalid UTF-8 sequences with U+FFFD REPLACEMENT CHARACTER.
+    Lossy,
 }
 
 /// OpenCC binding for Rust.
 pub struct OpenCC {
     opencc: Mutex<*mut c_void>,
+    config_file_path: PathBuf,
+    /// The characters that can start a change, or `None` if the dictionaries cannot be read.
+    key_starts: OnceLock<Option<HashSet<char>>>,
 }
 
 unsafe impl Send for OpenCC {}
@@ -223,8 +410,9 @@ unsafe impl Sync for OpenCC {}
 impl OpenCC {
     /// Create a new OpenCC instance through a file provided by its path.
     pub fn new<P: AsRef<Path>>(config_file_path: P) -> Result<Self, OpenCCError> {
+        let config_file_path = config_file_path.as_ref();
+
         let path_str = config_file_path
-            .as_ref()
             .to_str()
             .ok_or(OpenCCError::InvalidConfigPath)?;
 
@@ -250,9 +438,18 @@ impl OpenCC {
 
         Ok(OpenCC {
             opencc: Mutex::new(opencc_ptr),
+            config_file_path: path::absolute(config_file_path)
+                .unwrap_or_else(|_| config_file_path.to_path_buf()),
+            key_starts: OnceLock::new(),
         })
     }
 
+    /// Get the absolute path of the config file this instance was opened with.
+    #[cfg(feature = "encoding")]
+    pub(crate) fn config_file_path(&self) -> &Path {
+        &self.config_file_path
+    }
+
     /// Convert a string to another string.
     pub fn convert<S: AsRef<str>>(&self, input: S) -> Result<String, OpenCCError> {
         let input_ref = input.as_ref();
@@ -390,6 +587,128 @@ impl OpenCC {
 
         Ok(())
     }
+
+    /// Convert a string, borrowing the input when the conversion does not change anything.
+    ///
+    /// The first call reads the dictionaries of the config, to find the characters that start a key
+    /// which converts to something else. Text without any of them cannot change, so it is returned as
+    /// `Cow::Borrowed` without calling into the OpenCC library at all. Other text is converted and
+    /// compared with the input, so an allocation is only kept when the output actually differs. When
+    /// the dictionaries cannot be read, for example because they are in the legacy `ocd` format, all
+    /// text is converted and compared.
+    ///
+    /// # Errors
+    ///
+    /// If the input contains NULL bytes, or if the C library conversion fails, an error is returned.
+    pub fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        if input.contains('\0') {
+            return Err(OpenCCError::InputContainsNull);
+        }
+
+        let key_starts = self
+            .key_starts
+            .get_or_init(|| dictionary::key_starts(&self.config_file_path).ok());
+
+        if let Some(key_starts) = key_starts
+            && !input.chars().any(|c| key_starts.contains(&c))
+        {
+            return Ok(Cow::Borrowed(input));
+        }
+
+        let output = self.convert(input)?;
+
+        if output == input {
+            Ok(Cow::Borrowed(input))
+        } else {
+            Ok(Cow::Owned(output))
+        }
+    }
+
+    /// Convert a byte slice to another byte vector.
+    ///
+    /// Unlike [`OpenCC::convert`], the input may contain NULL bytes. The input is split around them,
+    /// every segment is converted separately and the NULL bytes are kept in place in the output.
+    /// `mode` decides whether invalid UTF-8 is an error or gets replaced.
+    ///
+    /// # Errors
+    ///
+    /// If `mode` is [`Utf8Mode::Strict`] and the input or output is not valid UTF-8, or if the C library
+    /// conversion fails, an error is returned.
+    pub fn convert_bytes<B: AsRef<[u8]>>(
+        &self,
+        input: B,
+        mode: Utf8Mode,
+    ) -> Result<Vec<u8>, OpenCCError> {
+        let input_ref = input.as_ref();
+
+        let input_str = match mode {
+            Utf8Mode::Strict => Cow::Borrowed(
+                std::str::from_utf8(input_ref)
+                    .map_err(|e| OpenCCError::InputInvalidUtf8(e.valid_up_to()))?,
+            ),
+            Utf8Mode::Lossy => String::from_utf8_lossy(input_ref),
+        };
+
+        let handle = self.opencc.lock().unwrap();
+        if handle.is_null() {
+            return Err(OpenCCError::NewInstanceFailed(
+                "OpenCC instance is not valid.".into(),
+            ));
+        }
+
+        let mut output = Vec::with_capacity(input_str.len());
+
+        for (i, segment) in input_str.split('\0').enumerate() {
+            if i > 0 {
+                output.push(0);
+            }
+
+            if segment.is_empty() {
+                continue;
+            }
+
+            let converted = convert_segment(*handle, segment)?;
+
+            match mode {
+                Utf8Mode::Strict => {
+                    std::str::from_utf8(&converted).map_err(|_| OpenCCError::InvalidUtf8)?;
+                    output.extend_from_slice(&converted);
+                }
+                Utf8Mode::Lossy => {
+                    output.extend_from_slice(String::from_utf8_lossy(&converted).as_bytes())
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Convert a segment that contains no NULL bytes with an already locked OpenCC handle.
+fn convert_segment(handle: *mut c_void, segment: &str) -> Result<Vec<u8>, OpenCCError> {
+    let c_input = CString::new(segment).map_err(|_| OpenCCError::InputContainsNull)?;
+
+    let result_ptr = unsafe { opencc_convert_utf8(handle, c_input.as_ptr(), segment.len()) };
+
+    if result_ptr.is_null() {
+        let error_msg = unsafe {
+            let err_ptr = opencc_error();
+            if err_ptr.is_null() {
+                "Unknown conversion error from OpenCC library".to_string()
+            } else {
+                CStr::from_ptr(err_ptr).to_string_lossy().into_owned()
+            }
+        };
+        return Err(OpenCCError::ConversionFailed(error_msg));
+    }
+
+    let result = unsafe { CStr::from_ptr(result_ptr) }.to_bytes().to_vec();
+
+    unsafe {
+        opencc_convert_utf8_free(result_ptr);
+    }
+
+    Ok(result)
 }
 
 impl Drop for OpenCC {
@@ -403,6 +722,8 @@ impl Drop for OpenCC {
     }
 }
 
+#[cfg(feature = "static-dictionaries")]
+use file::TempFile;
 #[cfg(feature = "static-dictionaries")]
 use phf::{Map, phf_map};
 
@@ -634,9 +955,12 @@ static CONFIG_MAP: Map<&'static str, &'static [&'static StaticDictionary]> = phf
 };
 
 #[cfg(feature = "static-dictionaries")]
+/// Write the files of a config into `path`. Existing files are kept, unless `replace_damaged` is set and their
+/// contents differ from the embedded ones.
 fn generate_static_dictionary_inner<P: AsRef<Path>>(
     path: P,
     config: DefaultConfig,
+    replace_damaged: bool,
 ) -> Result<(), Box<dyn Error>> {
     let path = path.as_ref();
     let config_filename = config.get_file_name();
@@ -645,10 +969,20 @@ fn generate_static_dictionary_inner<P: AsRef<Path>>(
         for data in *dictionaries_to_write {
             let output_path = path.join(data.0);
 
-            if !output_path.exists() {
-                let mut file = File::create(output_path)?;
+            let write = if replace_damaged {
+                fs::read(&output_path).ok().as_deref() != Some(data.1)
+            } else {
+                !output_path.exists()
+            };
+
+            if write {
+                // Write through a temporary file, so other processes never open a partially written dictionary.
+                let (temp, mut file) = TempFile::new(&output_path)?;
                 file.write_all(data.1)?;
                 file.flush()?;
+                drop(file);
+
+                temp.persist(&output_path)?;
             }
         }
     } else {
@@ -660,6 +994,8 @@ fn generate_static_dictionary_inner<P: AsRef<Path>>(
 
 #[cfg(feature = "static-dictionaries")]
 /// Generate files for a specific dictionary. These files are used for opening a new OpenCC instance.
+///
+/// Files that already exist in `path` are kept as they are, so customized dictionaries are not replaced.
 pub fn generate_static_dictionary<P: AsRef<Path>>(
     path: P,
     config: DefaultConfig,
@@ -678,11 +1014,13 @@ pub fn generate_static_dictionary<P: AsRef<Path>>(
         fs::create_dir_all(path)?;
     }
 
-    generate_static_dictionary_inner(path, config)
+    generate_static_dictionary_inner(path, config, false)
 }
 
 #[cfg(feature = "static-dictionaries")]
 /// Generate files for specific dictionaries. These files are used for opening a new OpenCC instance.
+///
+/// Files that already exist in `path` are kept as they are, so customized dictionaries are not replaced.
 pub fn generate_static_dictionaries<P: AsRef<Path>>(
     path: P,
     configs: &[DefaultConfig],
@@ -701,8 +1039,72 @@ pub fn generate_static_dictionaries<P: AsRef<Path>>(
     }
 
     for config in configs.iter().copied() {
-        generate_static_dictionary_inner(path, config)?
+        generate_static_dictionary_inner(path, config, false)?
     }
 
     Ok(())
 }
+
+#[cfg(feature = "static-dictionaries")]
+impl OpenCC {
+    /// Create a new OpenCC instance for a default config, using the static dictionaries.
+    ///
+    /// The dictionaries are generated into a directory in the system temporary directory, which is private
+    /// to the current user and shared by their processes that use the same version of this crate. Files that
+    /// already exist are reused if their contents are intact.
+    pub fn from_default_config(config: DefaultConfig) -> Result<Self, OpenCCError> {
+        let path = static_dictionary_dir()?;
+
+        // The directory belongs to this crate, so leftovers of an interrupted write are repaired.
+        generate_static_dictionary_inner(&path, config, true)
+            .map_err(|e| OpenCCError::NewInstanceFailed(e.to_string()))?;
+
+        OpenCC::new(path.join(config.get_file_name()))
+    }
+}
+
+#[cfg(all(feature = "static-dictionaries", unix))]
+fn static_dictionary_dir() -> Result<PathBuf, OpenCCError> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    let uid = unsafe { libc::geteuid() };
+
+    let path = env::temp_dir().join(format!(
+        "opencc-rust-windows-{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        uid
+    ));
+
+    match fs::DirBuilder::new().mode(0o700).create(&path) {
+        Ok(()) => (),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    // The temporary directory is shared, so only a directory that nobody else can write to is trusted.
+    let metadata = fs::symlink_metadata(&path)?;
+
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "The path '{}' is not a private directory of the current user.",
+                path.display()
+            ),
+        )
+        .into());
+    }
+
+    Ok(path)
+}
+
+#[cfg(all(feature = "static-dictionaries", not(unix)))]
+fn static_dictionary_dir() -> Result<PathBuf, OpenCCError> {
+    // The temporary directory is already private to the user on Windows.
+    let path = env::temp_dir().join(concat!("opencc-rust-windows-", env!("CARGO_PKG_VERSION")));
+
+    fs::create_dir_all(&path)?;
+
+    Ok(path)
+}
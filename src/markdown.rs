@@ -0,0 +1,225 @@
+use std::ops::Range;
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+
+use crate::{OpenCC, OpenCCError};
+
+/// Options for [`OpenCC::convert_markdown`].
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// Convert the alt text of images. The default is `false`.
+    pub alt_text: bool,
+    /// Convert the titles of links, images and link reference definitions. The default is `false`.
+    pub titles: bool,
+    /// The top-level front-matter keys whose values are converted. The default is `title`, `description`
+    /// and `summary`.
+    pub front_matter_keys: Vec<String>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            alt_text: false,
+            titles: false,
+            front_matter_keys: vec!["title".into(), "description".into(), "summary".into()],
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert the prose of a Markdown document.
+    ///
+    /// The document is parsed as CommonMark with tables, footnotes, strikethrough and task lists, and
+    /// only the source text of prose nodes is converted. Code blocks, inline code, link destinations,
+    /// autolinks and HTML are left as they are, and everything outside the converted text is copied
+    /// byte for byte.
+    ///
+    /// A YAML (`---`) or TOML (`+++`) front-matter block at the start of the document is recognized,
+    /// and only the values of `options.front_matter_keys` in it are converted.
+    ///
+    /// # Errors
+    ///
+    /// If the C library conversion fails, an error is returned.
+    pub fn convert_markdown<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &MarkdownOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut ranges = Vec::new();
+
+        let body_start = front_matter_ranges(input, &options.front_matter_keys, &mut ranges);
+
+        prose_ranges(&input[body_start..], body_start, options, &mut ranges);
+
+        ranges.sort_by_key(|range| range.start);
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for range in ranges {
+            // Ranges can only overlap when a title could not be located exactly, keep the first one.
+            if range.start < last {
+                continue;
+            }
+
+            output.push_str(&input[last..range.start]);
+            output.push_str(&self.convert_cow(&input[range.clone()])?);
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
+
+/// Collect the ranges of the converted front-matter values and return where the front matter ends.
+fn front_matter_ranges(input: &str, keys: &[String], ranges: &mut Vec<Range<usize>>) -> usize {
+    let mut lines = input.split_inclusive('\n');
+
+    let (yaml, fence) = match lines.next().map(str::trim_end) {
+        Some("---") => (true, "---"),
+        Some("+++") => (false, "+++"),
+        _ => return 0,
+    };
+
+    let separator = if yaml { ':' } else { '=' };
+    let count = ranges.len();
+
+    let mut start = input.find('\n').map_or(input.len(), |i| i + 1);
+    let mut converting = false;
+    let mut in_table = false;
+
+    for line in lines {
+        let end = start + line.len();
+        let content = line.trim_end();
+
+        if content == fence || (yaml && content == "...") {
+            return end;
+        }
+
+        if content.is_empty() || content.starts_with('#') {
+            // Blank lines and comments.
+        } else if line.starts_with([' ', '\t']) || (yaml && line.starts_with('-')) {
+            // Continuation lines of the previous value, like the items of a list.
+            if converting {
+                ranges.push(start..start + content.len());
+            }
+        } else if !yaml && line.starts_with(['[', ']']) {
+            // The end of a multi-line array, or a table header after which no key is top-level.
+            converting = false;
+            in_table |= line.starts_with('[');
+        } else {
+            converting = false;
+
+            if !in_table && let Some(i) = content.find(separator) {
+                let key = content[..i].trim().trim_matches(['"', '\'']);
+
+                if keys.iter().any(|k| k == key) {
+                    converting = true;
+                    ranges.push(start + i + 1..start + content.len());
+                }
+            }
+        }
+
+        start = end;
+    }
+
+    // Without a closing fence, this is not front matter.
+    ranges.truncate(count);
+
+    0
+}
+
+/// Collect the ranges of the converted text of a Markdown body which starts at `offset` in the document.
+fn prose_ranges(
+    body: &str,
+    offset: usize,
+    options: &MarkdownOptions,
+    ranges: &mut Vec<Range<usize>>,
+) {
+    let parser = Parser::new_ext(
+        body,
+        Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_HEADING_ATTRIBUTES,
+    )
+    .into_offset_iter();
+
+    let mut push = |range: Range<usize>| ranges.push(offset + range.start..offset + range.end);
+
+    if options.titles {
+        for (_, definition) in parser.reference_definitions().iter() {
+            if let Some(title) = &definition.title
+                && let Some(range) = locate_title(body, definition.span.clone(), title)
+            {
+                push(range);
+            }
+        }
+    }
+
+    let mut code_blocks = 0usize;
+    let mut images = 0usize;
+    // Whether each open link is an autolink, whose text is a URL or an email address.
+    let mut links = Vec::new();
+
+    for (event, range) in parser {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => code_blocks += 1,
+            Event::End(TagEnd::CodeBlock) => code_blocks -= 1,
+            Event::Start(Tag::Image {
+                link_type, title, ..
+            }) => {
+                images += 1;
+
+                if options.titles
+                    && link_type == LinkType::Inline
+                    && let Some(range) = locate_title(body, range, &title)
+                {
+                    push(range);
+                }
+            }
+            Event::End(TagEnd::Image) => images -= 1,
+            Event::Start(Tag::Link {
+                link_type, title, ..
+            }) => {
+                links.push(matches!(link_type, LinkType::Autolink | LinkType::Email));
+
+                if options.titles
+                    && link_type == LinkType::Inline
+                    && let Some(range) = locate_title(body, range, &title)
+                {
+                    push(range);
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                links.pop();
+            }
+            Event::Text(_)
+                if code_blocks == 0
+                    && !links.iter().any(|&autolink| autolink)
+                    && (images == 0 || options.alt_text) =>
+            {
+                push(range)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Find the source range of a title, which comes last in a link, an image or a reference definition.
+/// Titles with escapes cannot be found in the source and are left out.
+fn locate_title(body: &str, span: Range<usize>, title: &str) -> Option<Range<usize>> {
+    if title.is_empty() || title.is_ascii() {
+        return None;
+    }
+
+    let start = span.start + body[span].rfind(title)?;
+
+    Some(start..start + title.len())
+}
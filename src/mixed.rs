@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use crate::{ConfigCache, DefaultConfig, OpenCCError};
+#[cfg(feature = "html")]
+use crate::{
+    HtmlOptions,
+    html::{Edit, html_edits},
+};
+
+const MARKER_OPEN: &str = "<!--opencc:";
+const MARKER_CLOSE: &str = "<!--/opencc-->";
+
+impl ConfigCache {
+    /// Convert a document paragraph by paragraph, with the config that `select` returns for each paragraph.
+    ///
+    /// Paragraphs are separated by blank lines, which are kept as they are. When `select` returns `None`,
+    /// the paragraph is left as it is.
+    ///
+    /// # Errors
+    ///
+    /// If a config cannot be opened or the C library conversion fails, an error is returned.
+    pub fn convert_paragraphs<S: AsRef<str>, F: FnMut(&str) -> Option<DefaultConfig>>(
+        &self,
+        input: S,
+        mut select: F,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+        // The start and the end of the content of the current paragraph.
+        let mut paragraph: Option<(usize, usize)> = None;
+        let mut line_start = 0;
+
+        for line in input.split_inclusive('\n').chain([""]) {
+            let content_end = line_start + line.trim_end_matches(['\n', '\r']).len();
+
+            if !line.trim().is_empty() {
+                paragraph = Some((
+                    paragraph.map_or(line_start, |(start, _)| start),
+                    content_end,
+                ));
+            } else if let Some((start, end)) = paragraph.take() {
+                let text = &input[start..end];
+
+                output.push_str(&input[last..start]);
+
+                match select(text) {
+                    Some(config) => output.push_str(&self.get(config)?.convert_cow(text)?),
+                    None => output.push_str(text),
+                }
+
+                last = end;
+            }
+
+            line_start += line.len();
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+
+    /// Convert a document whose spans choose their configs with markers.
+    ///
+    /// A span starts with a marker like `<!--opencc:hk2t-->` and ends with `<!--/opencc-->`. Spans can be
+    /// nested, and `<!--opencc:none-->` starts a span that is left as it is. The text outside any span is
+    /// converted with `default`, or left as it is if `default` is `None`. The markers are kept in the
+    /// output.
+    ///
+    /// # Errors
+    ///
+    /// If a marker names an unknown config, a config cannot be opened or the C library conversion fails,
+    /// an error is returned.
+    pub fn convert_marked<S: AsRef<str>>(
+        &self,
+        input: S,
+        default: Option<DefaultConfig>,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut output = String::with_capacity(input.len());
+        let mut configs = vec![default];
+        let mut rest = input;
+
+        loop {
+            let open = rest.find(MARKER_OPEN);
+            let close = rest.find(MARKER_CLOSE);
+
+            let next = match (open, close) {
+                (Some(open), Some(close)) => open.min(close),
+                (Some(n), None) | (None, Some(n)) => n,
+                (None, None) => rest.len(),
+            };
+
+            let text = &rest[..next];
+
+            match configs.last().copied().flatten() {
+                Some(config) if !text.is_empty() => {
+                    output.push_str(&self.get(config)?.convert_cow(text)?)
+                }
+                _ => output.push_str(text),
+            }
+
+            rest = &rest[next..];
+
+            if rest.is_empty() {
+                break;
+            }
+
+            let marker_end = if Some(next) == close {
+                // An unbalanced closing marker is kept without changing the config.
+                if configs.len() > 1 {
+                    configs.pop();
+                }
+
+                MARKER_CLOSE.len()
+            } else {
+                let Some(end) = rest.find("-->") else {
+                    output.push_str(rest);
+                    break;
+                };
+
+                let name = rest[MARKER_OPEN.len()..end].trim();
+
+                configs.push(if name.eq_ignore_ascii_case("none") {
+                    None
+                } else {
+                    Some(DefaultConfig::from_str(name)?)
+                });
+
+                end + 3
+            };
+
+            output.push_str(&rest[..marker_end]);
+            rest = &rest[marker_end..];
+        }
+
+        Ok(output)
+    }
+
+    /// Convert an HTML or XML document with the config that `select` returns for the `lang` or `xml:lang`
+    /// value in scope of each span, which is `None` outside any element with a language.
+    ///
+    /// The document is read like [`OpenCC::convert_html`](crate::OpenCC::convert_html) reads it, except
+    /// that `options.language_tag` is ignored: each Chinese `lang` value is replaced with the
+    /// [`DefaultConfig::target_language_tag`] of the config chosen for it. When `select` returns `None`,
+    /// the span is left as it is.
+    ///
+    /// # Errors
+    ///
+    /// If a config cannot be opened or the C library conversion fails, an error is returned.
+    #[cfg(feature = "html")]
+    pub fn convert_html_by_language<
+        S: AsRef<str>,
+        F: FnMut(Option<&str>) -> Option<DefaultConfig>,
+    >(
+        &self,
+        input: S,
+        options: &HtmlOptions,
+        mut select: F,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for (range, edit) in html_edits(input, options) {
+            output.push_str(&input[last..range.start]);
+
+            let text = &input[range.clone()];
+
+            match edit {
+                Edit::Convert { lang } => match select(lang.map(|lang| &input[lang])) {
+                    Some(config) => output.push_str(&self.get(config)?.convert_cow(text)?),
+                    None => output.push_str(text),
+                },
+                Edit::Language => output.push_str(
+                    select(Some(text))
+                        .and_then(DefaultConfig::target_language_tag)
+                        .unwrap_or(text),
+                ),
+            }
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
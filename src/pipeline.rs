@@ -0,0 +1,291 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Formatter},
+    mem,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{Converter, OpenCCError};
+
+/// A built-in normalization step of a [`Pipeline`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Normalization {
+    /// Replace full-width ASCII variants (`Ａ`, `１`, `！`) with ASCII, and the ideographic space with a space.
+    HalfwidthAscii,
+    /// Replace `\r\n` and lone `\r` with `\n`.
+    UnixLineEndings,
+    /// Remove the whitespace at the end of each line.
+    TrimLineEnds,
+}
+
+impl Normalization {
+    /// Get the name of the step, which is used in the timings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Normalization::HalfwidthAscii => "halfwidth-ascii",
+            Normalization::UnixLineEndings => "unix-line-endings",
+            Normalization::TrimLineEnds => "trim-line-ends",
+        }
+    }
+
+    fn apply(self, input: &str, output: &mut String) {
+        match self {
+            Normalization::HalfwidthAscii => output.extend(input.chars().map(|c| match c {
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '\u{3000}' => ' ',
+                c => c,
+            })),
+            Normalization::UnixLineEndings => {
+                let mut rest = input;
+
+                while let Some(n) = rest.find('\r') {
+                    output.push_str(&rest[..n]);
+                    output.push('\n');
+
+                    rest = rest[n + 1..].strip_prefix('\n').unwrap_or(&rest[n + 1..]);
+                }
+
+                output.push_str(rest);
+            }
+            Normalization::TrimLineEnds => {
+                for line in input.split_inclusive('\n') {
+                    let content = line.trim_end_matches(['\n', '\r']);
+
+                    output.push_str(content.trim_end());
+                    output.push_str(&line[content.len()..]);
+                }
+            }
+        }
+    }
+}
+
+type Transform = dyn Fn(&str, &mut String) -> Result<(), OpenCCError> + Send + Sync;
+
+enum Step {
+    Converter(Box<dyn Converter + Send + Sync>),
+    Transform(Box<Transform>),
+    Normalization(Normalization),
+}
+
+struct Stage {
+    name: String,
+    step: Step,
+    calls: AtomicU64,
+    nanos: AtomicU64,
+}
+
+/// The accumulated timing of a stage of a [`Pipeline`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StageTiming {
+    /// The name of the stage.
+    pub name: String,
+    /// The number of times the stage has run.
+    pub calls: u64,
+    /// The total time spent in the stage.
+    pub total: Duration,
+}
+
+/// A sequence of converters, Rust transforms and normalization steps that runs as one converter.
+///
+/// Each stage reads the output of the previous one. The intermediate buffers alternate between the stages
+/// and are kept for later calls, so the stages do not allocate once the buffers are large enough. Only the
+/// result is allocated: [`Converter::convert`] copies it into a new string, while [`Converter::convert_append`]
+/// appends it to the given one. The time spent in each stage is accumulated and can be read with
+/// [`Pipeline::timings`].
+///
+/// ```rust
+/// use opencc_rust_windows::{Converter, MappingConverter, Normalization, Pipeline};
+///
+/// let pipeline = Pipeline::new()
+///     .then_normalize(Normalization::HalfwidthAscii)
+///     .then("phrases", MappingConverter::new().with("软件", "軟體"))
+///     .then_fn("brackets", |input, output| {
+///         output.push_str(&input.replace('「', "“").replace('」', "”"));
+///
+///         Ok(())
+///     });
+///
+/// assert_eq!("“軟體” 1.0", pipeline.convert("「软件」　１．０").unwrap());
+/// assert_eq!(3, pipeline.timings().len());
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+    buffers: Mutex<Vec<String>>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline, which returns its input unchanged.
+    #[inline]
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    fn push(mut self, name: String, step: Step) -> Self {
+        self.stages.push(Stage {
+            name,
+            step,
+            calls: AtomicU64::new(0),
+            nanos: AtomicU64::new(0),
+        });
+
+        self
+    }
+
+    /// Append a converter, like an [`OpenCC`](crate::OpenCC) handle or another pipeline.
+    #[inline]
+    pub fn then<N: Into<String>, C: Converter + Send + Sync + 'static>(
+        self,
+        name: N,
+        converter: C,
+    ) -> Self {
+        self.push(name.into(), Step::Converter(Box::new(converter)))
+    }
+
+    /// Append a transform that reads the current text and appends its result to the given buffer, which is
+    /// empty when the transform is called.
+    #[inline]
+    pub fn then_fn<
+        N: Into<String>,
+        F: Fn(&str, &mut String) -> Result<(), OpenCCError> + Send + Sync + 'static,
+    >(
+        self,
+        name: N,
+        transform: F,
+    ) -> Self {
+        self.push(name.into(), Step::Transform(Box::new(transform)))
+    }
+
+    /// Append a built-in normalization step, named after [`Normalization::name`].
+    #[inline]
+    pub fn then_normalize(self, normalization: Normalization) -> Self {
+        self.push(
+            normalization.name().to_string(),
+            Step::Normalization(normalization),
+        )
+    }
+
+    /// Get the number of stages.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Whether the pipeline has no stages.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Get the accumulated timing of each stage, in order.
+    pub fn timings(&self) -> Vec<StageTiming> {
+        self.stages
+            .iter()
+            .map(|stage| StageTiming {
+                name: stage.name.clone(),
+                calls: stage.calls.load(Ordering::Relaxed),
+                total: Duration::from_nanos(stage.nanos.load(Ordering::Relaxed)),
+            })
+            .collect()
+    }
+
+    /// Reset the accumulated timings.
+    pub fn reset_timings(&self) {
+        for stage in &self.stages {
+            stage.calls.store(0, Ordering::Relaxed);
+            stage.nanos.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Run the stages on `input`, and return the buffer holding the result. The other buffer is returned to
+    /// the pool, and so are both buffers if a stage fails.
+    fn run(&self, input: &str) -> Result<String, OpenCCError> {
+        let (mut current, mut next) = {
+            let mut buffers = self.buffers.lock().unwrap();
+
+            (
+                buffers.pop().unwrap_or_default(),
+                buffers.pop().unwrap_or_default(),
+            )
+        };
+
+        current.clear();
+        current.push_str(input);
+
+        let result = self.stages.iter().try_for_each(|stage| {
+            let start = Instant::now();
+
+            next.clear();
+
+            match &stage.step {
+                Step::Converter(converter) => converter.convert_append(&current, &mut next)?,
+                Step::Transform(transform) => transform(&current, &mut next)?,
+                Step::Normalization(normalization) => normalization.apply(&current, &mut next),
+            }
+
+            mem::swap(&mut current, &mut next);
+
+            stage.calls.fetch_add(1, Ordering::Relaxed);
+            stage.nanos.fetch_add(
+                start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+
+            Ok(())
+        });
+
+        self.recycle(next);
+
+        match result {
+            Ok(()) => Ok(current),
+            Err(e) => {
+                self.recycle(current);
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Return a buffer that is no longer needed to the pool.
+    fn recycle(&self, buffer: String) {
+        self.buffers.lock().unwrap().push(buffer);
+    }
+}
+
+impl Converter for Pipeline {
+    #[inline]
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        if self.stages.is_empty() {
+            return Ok(Cow::Borrowed(input));
+        }
+
+        // Copy the result, so the grown buffer stays in the pool.
+        let result = self.run(input)?;
+        let output = result.as_str().to_owned();
+
+        self.recycle(result);
+
+        Ok(Cow::Owned(output))
+    }
+
+    fn convert_append(&self, input: &str, output: &mut String) -> Result<(), OpenCCError> {
+        let result = self.run(input)?;
+
+        output.push_str(&result);
+        self.recycle(result);
+
+        Ok(())
+    }
+}
+
+impl Debug for Pipeline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.stages.iter().map(|stage| &stage.name))
+            .finish()
+    }
+}
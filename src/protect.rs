@@ -0,0 +1,300 @@
+use std::ops::Range;
+
+use crate::{OpenCC, OpenCCError};
+
+/// Printf conversion characters, including `@` for Objective-C objects.
+const PRINTF_CONVERSIONS: &[u8] = b"diouxXeEfFgGaAcspn@";
+
+/// Options for [`OpenCC::convert_with_options`], which choose the spans that are never converted.
+///
+/// Some fields only exist with some features, and more may be added later, so the options are created with
+/// [`ConvertOptions::default`] and then changed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ConvertOptions {
+    /// Terms that are never converted, like person names or quoted source text. The default is empty.
+    pub protected_terms: Vec<String>,
+    /// Pairs of opening and closing markers whose content is never converted. The markers themselves are
+    /// kept in the output. The default is `<!--nocc-->` and `<!--/nocc-->`.
+    pub markers: Vec<(String, String)>,
+    /// Protect URLs, like `https://example.com/软件` or `www.example.com`. The default is `true`.
+    pub urls: bool,
+    /// Protect email addresses. The default is `true`.
+    pub emails: bool,
+    /// Protect placeholders, like `{0}`, `{name}`, `%s`, `%1$d` and `%(name)s`. The default is `true`.
+    pub placeholders: bool,
+    /// Regular expressions whose matches are never converted. The default is empty.
+    #[cfg(feature = "regex")]
+    pub patterns: Vec<regex::Regex>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        ConvertOptions {
+            protected_terms: Vec::new(),
+            markers: vec![("<!--nocc-->".into(), "<!--/nocc-->".into())],
+            urls: true,
+            emails: true,
+            placeholders: true,
+            #[cfg(feature = "regex")]
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert a string, leaving the spans protected by `options` as they are.
+    ///
+    /// The text around the protected spans is converted separately, so a phrase never spans the edge of a
+    /// protected span. Protected spans are copied byte for byte.
+    ///
+    /// # Errors
+    ///
+    /// If the C library conversion fails, an error is returned.
+    pub fn convert_with_options<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &ConvertOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for range in protected_ranges(input, options) {
+            output.push_str(&self.convert_cow(&input[last..range.start])?);
+            output.push_str(&input[range.clone()]);
+
+            last = range.end;
+        }
+
+        output.push_str(&self.convert_cow(&input[last..])?);
+
+        Ok(output)
+    }
+}
+
+/// Collect the protected spans of a string, sorted and merged.
+pub(crate) fn protected_ranges(input: &str, options: &ConvertOptions) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+
+    for term in options.protected_terms.iter().filter(|t| !t.is_empty()) {
+        ranges.extend(
+            input
+                .match_indices(term.as_str())
+                .map(|(start, _)| start..start + term.len()),
+        );
+    }
+
+    for (open, close) in options.markers.iter().filter(|(o, _)| !o.is_empty()) {
+        let mut from = 0;
+
+        while let Some(start) = input[from..].find(open.as_str()).map(|n| from + n) {
+            let content_start = start + open.len();
+            let end = match input[content_start..].find(close.as_str()) {
+                Some(n) if !close.is_empty() => content_start + n + close.len(),
+                // An unclosed marker protects the rest of the input.
+                _ => input.len(),
+            };
+
+            ranges.push(start..end);
+            from = end;
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    for pattern in &options.patterns {
+        ranges.extend(
+            pattern
+                .find_iter(input)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range()),
+        );
+    }
+
+    if options.urls {
+        url_ranges(input, &mut ranges);
+    }
+
+    if options.emails {
+        email_ranges(input, &mut ranges);
+    }
+
+    if options.placeholders {
+        placeholder_ranges(input, &mut ranges);
+    }
+
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+fn url_ranges(input: &str, ranges: &mut Vec<Range<usize>>) {
+    let bytes = input.as_bytes();
+
+    for (separator, _) in input.match_indices("://") {
+        let start = bytes[..separator]
+            .iter()
+            .rposition(|&b| !(b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')))
+            .map_or(0, |n| n + 1);
+
+        // A scheme starts with a letter.
+        let Some(first) = bytes[start..separator]
+            .iter()
+            .position(|b| b.is_ascii_alphabetic())
+        else {
+            continue;
+        };
+
+        let start = start + first;
+        let end = url_end(input, separator + 3);
+
+        if end > separator + 3 {
+            ranges.push(start..end);
+        }
+    }
+
+    for (start, _) in input.match_indices("www.") {
+        let after_word = start == 0 || !is_url_byte(bytes[start - 1]);
+        let end = url_end(input, start + 4);
+
+        if after_word && end > start + 4 {
+            ranges.push(start..end);
+        }
+    }
+}
+
+/// Find the end of a URL whose address starts at `from`.
+fn url_end(input: &str, from: usize) -> usize {
+    let end = input[from..]
+        .char_indices()
+        .find(|&(_, c)| {
+            c.is_whitespace()
+                || matches!(c, '<' | '>' | '"' | '\'' | '`')
+                // CJK punctuation and full-width forms end a URL written in Chinese prose.
+                || matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}')
+        })
+        .map_or(input.len(), |(n, _)| from + n);
+
+    // Punctuation at the end of a sentence is not part of the URL.
+    from + input[from..end]
+        .trim_end_matches(['.', ',', ';', ':', '!', '?', ')'])
+        .len()
+}
+
+fn is_url_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b'/' | b':' | b'@')
+}
+
+fn email_ranges(input: &str, ranges: &mut Vec<Range<usize>>) {
+    let bytes = input.as_bytes();
+    let is_local =
+        |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-');
+    let is_domain = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-');
+
+    for (at, _) in input.match_indices('@') {
+        let start = bytes[..at]
+            .iter()
+            .rposition(|&b| !is_local(b))
+            .map_or(0, |n| n + 1);
+
+        let end = bytes[at + 1..]
+            .iter()
+            .position(|&b| !is_domain(b))
+            .map_or(bytes.len(), |n| at + 1 + n);
+
+        let domain = input[at + 1..end].trim_end_matches(['.', '-']);
+
+        if start < at && domain.contains('.') && !domain.starts_with('.') {
+            ranges.push(start..at + 1 + domain.len());
+        }
+    }
+}
+
+fn placeholder_ranges(input: &str, ranges: &mut Vec<Range<usize>>) {
+    let bytes = input.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if bytes.get(i + 1) == Some(&b'%') => i += 2,
+            b'%' => match printf_end(bytes, i) {
+                Some(end) => {
+                    ranges.push(i..end);
+                    i = end;
+                }
+                None => i += 1,
+            },
+            b'{' => {
+                let length = bytes[i + 1..]
+                    .iter()
+                    .take_while(|&&b| {
+                        b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b':' | b'-')
+                    })
+                    .count();
+
+                if length > 0 && bytes.get(i + 1 + length) == Some(&b'}') {
+                    ranges.push(i..i + length + 2);
+                    i += length + 2;
+                } else {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Find the end of the printf conversion, like `%s`, `%1$d` or `%(name)s`, whose `%` is at `start`.
+pub(crate) fn printf_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut j = start + 1;
+
+    if bytes.get(j) == Some(&b'(') {
+        // A Python mapping key, like `%(name)s`.
+        j += bytes[j..].iter().position(|&b| b == b')' || b == b'\n')?;
+
+        if bytes[j] != b')' {
+            return None;
+        }
+
+        j += 1;
+    } else {
+        // An optional argument index, like `1$`.
+        let digits = bytes[j..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+        if digits > 0 && bytes.get(j + digits) == Some(&b'$') {
+            j += digits + 1;
+        }
+    }
+
+    j += bytes[j..]
+        .iter()
+        .take_while(|&&b| matches!(b, b'-' | b'+' | b' ' | b'#' | b'0'))
+        .count();
+    j += bytes[j..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    if bytes.get(j) == Some(&b'.') {
+        j += 1;
+        j += bytes[j..].iter().take_while(|b| b.is_ascii_digit()).count();
+    }
+
+    // Length modifiers, like `ld` or `lld`.
+    j += bytes[j..]
+        .iter()
+        .take_while(|&&b| matches!(b, b'h' | b'l' | b'L' | b'q' | b'j' | b'z' | b't'))
+        .count();
+
+    bytes
+        .get(j)
+        .is_some_and(|b| PRINTF_CONVERSIONS.contains(b))
+        .then_some(j + 1)
+}
@@ -0,0 +1,419 @@
+use std::{
+    borrow::Cow,
+    env, fs,
+    io::Write,
+    mem,
+    path::Path,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use regex::Regex;
+
+use crate::{Converter, OpenCC, OpenCCError, Pipeline, file::TempFile, json::Json};
+
+/// How a rule finds the text it replaces.
+#[derive(Debug, Clone)]
+enum Pattern {
+    Literal(String),
+    Regex(Regex),
+}
+
+/// A condition on the character next to a match.
+#[derive(Debug, Clone)]
+struct Condition {
+    /// Whether the condition is about the character after the match instead of the one before it.
+    next: bool,
+    negated: bool,
+    /// Matches a whole character.
+    class: Regex,
+}
+
+impl Condition {
+    fn holds(&self, input: &str, start: usize, end: usize) -> bool {
+        let c = if self.next {
+            input[end..].chars().next()
+        } else {
+            input[..start].chars().next_back()
+        };
+
+        let matched = c.is_some_and(|c| self.class.is_match(c.encode_utf8(&mut [0; 4])));
+
+        matched != self.negated
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Pattern,
+    replacement: String,
+    conditions: Vec<Condition>,
+}
+
+impl Rule {
+    /// Apply the rule to `input`, appending the result to `output`. Return whether anything was replaced.
+    fn apply(&self, input: &str, output: &mut String) -> bool {
+        let mut last = 0;
+
+        let mut replace = |start: usize, end: usize, output: &mut String| -> bool {
+            if !self.conditions.iter().all(|c| c.holds(input, start, end)) {
+                return false;
+            }
+
+            output.push_str(&input[last..start]);
+            last = end;
+
+            true
+        };
+
+        match &self.pattern {
+            Pattern::Literal(literal) => {
+                for (start, _) in input.match_indices(literal.as_str()) {
+                    if replace(start, start + literal.len(), output) {
+                        output.push_str(&self.replacement);
+                    }
+                }
+            }
+            Pattern::Regex(regex) => {
+                for captures in regex.captures_iter(input) {
+                    let m = captures.get(0).unwrap();
+
+                    // An empty match cannot replace anything.
+                    if m.is_empty() {
+                        continue;
+                    }
+
+                    if replace(m.start(), m.end(), output) {
+                        captures.expand(&self.replacement, output);
+                    }
+                }
+            }
+        }
+
+        // Every replacement moves `last` past a non-empty match.
+        if last == 0 {
+            return false;
+        }
+
+        output.push_str(&input[last..]);
+
+        true
+    }
+}
+
+/// An ordered list of rewrite rules, for differences that are not dictionary lookups, like quotation mark
+/// styles or spacing around full-width punctuation.
+///
+/// A rule file has one rule per line, with tab-separated fields like the OpenCC text dictionaries. Empty lines
+/// and lines starting with `#` are ignored.
+///
+/// ```text
+/// literal<TAB>pattern<TAB>replacement[<TAB>condition...]
+/// regex<TAB>pattern<TAB>replacement[<TAB>condition...]
+/// ```
+///
+/// In literal patterns and replacements, `\t`, `\n`, `\r`, `\s` (a space) and `\\` are escapes. Regex
+/// replacements can refer to captures like `$1` or `${name}`. A condition tests the character before
+/// (`prev=`) or after (`next=`) each match against a regex, and `!prev=` and `!next=` negate it. At the
+/// start or the end of the text, there is no character, so `prev=` and `next=` fail and the negated
+/// conditions hold.
+///
+/// The rules are applied in order, each one to the output of the previous one. A rule set is a
+/// [`Converter`], so it can run before or after an OpenCC handle in a [`Pipeline`], and it can be a step of
+/// a custom config opened with [`Pipeline::from_config`].
+///
+/// ```rust
+/// use opencc_rust_windows::{Converter, RuleSet};
+///
+/// let rules = RuleSet::parse("literal\t“\t「\nliteral\t”\t」\nregex\t\\s+([，。])\t$1\tprev=\\p{Han}").unwrap();
+///
+/// assert_eq!("「你好」 ，再見。", rules.convert("“你好” ，再見 。").unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Parse a rule file.
+    ///
+    /// # Errors
+    ///
+    /// If a line is not a valid rule, an error with its line number is returned.
+    pub fn parse<S: AsRef<str>>(source: S) -> Result<Self, OpenCCError> {
+        let mut rules = Vec::new();
+
+        for (i, line) in source.as_ref().lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            rules.push(
+                parse_rule(line).map_err(|message| OpenCCError::InvalidDocument {
+                    format: "rules",
+                    message: format!("line {}: {}", i + 1, message),
+                })?,
+            );
+        }
+
+        Ok(RuleSet { rules })
+    }
+
+    /// Read and parse a rule file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or a line is not a valid rule, an error is returned.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, OpenCCError> {
+        RuleSet::parse(fs::read_to_string(path)?)
+    }
+
+    /// Get the number of rules.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether there are no rules.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl Converter for RuleSet {
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        let mut current = Cow::Borrowed(input);
+        let mut output = String::new();
+
+        for rule in &self.rules {
+            output.clear();
+
+            if rule.apply(&current, &mut output) {
+                current = Cow::Owned(mem::take(&mut output));
+            }
+        }
+
+        Ok(current)
+    }
+}
+
+fn parse_rule(line: &str) -> Result<Rule, String> {
+    let mut fields = line.trim_end_matches('\r').split('\t');
+
+    let kind = fields.next().unwrap_or_default();
+
+    let (Some(pattern), Some(replacement)) = (fields.next(), fields.next()) else {
+        return Err("a rule needs a kind, a pattern and a replacement".to_string());
+    };
+
+    if pattern.is_empty() {
+        return Err("the pattern is empty".to_string());
+    }
+
+    let (pattern, replacement) = match kind {
+        "literal" => (Pattern::Literal(unescape(pattern)?), unescape(replacement)?),
+        "regex" => (
+            Pattern::Regex(Regex::new(pattern).map_err(|e| e.to_string())?),
+            replacement.to_string(),
+        ),
+        _ => return Err(format!("unknown rule kind {:?}", kind)),
+    };
+
+    let conditions = fields
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (name, class) = field
+                .split_once('=')
+                .ok_or_else(|| format!("invalid condition {:?}", field))?;
+
+            let (negated, name) = match name.strip_prefix('!') {
+                Some(name) => (true, name),
+                None => (false, name),
+            };
+
+            let next = match name {
+                "prev" => false,
+                "next" => true,
+                _ => return Err(format!("unknown condition {:?}", name)),
+            };
+
+            Ok(Condition {
+                next,
+                negated,
+                class: Regex::new(&format!("^(?:{})$", class)).map_err(|e| e.to_string())?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(Rule {
+        pattern,
+        replacement,
+        conditions,
+    })
+}
+
+fn unescape(field: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(field.len());
+    let mut chars = field.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+
+            continue;
+        }
+
+        output.push(match chars.next() {
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('s') => ' ',
+            Some('\\') => '\\',
+            Some(c) => return Err(format!("unknown escape \\{}", c)),
+            None => return Err("the field ends with a backslash".to_string()),
+        });
+    }
+
+    Ok(output)
+}
+
+/// Used to give the generated configs unique names.
+static GENERATED_CONFIGS: AtomicUsize = AtomicUsize::new(0);
+
+impl Pipeline {
+    /// Open an OpenCC config whose conversion chain can contain rule steps.
+    ///
+    /// A rule step is an entry like `{"rules": "quotes.rules"}` in `conversion_chain`, with a path relative
+    /// to the config. The dictionary steps between rule steps are opened as OpenCC handles with the same
+    /// segmentation, so the steps run in the order of the chain. Each stage is named after its rule file, or
+    /// after the config and the position of its first dictionary step.
+    ///
+    /// Every handle segments its input again with the segmenter of the config. A split chain segments the
+    /// converted output of the previous stage instead of the original text, so its result can differ from
+    /// the same chain run by one handle, even where no rule matches.
+    ///
+    /// # Errors
+    ///
+    /// If the config or a rule file cannot be read or parsed, or a handle cannot be opened, an error is
+    /// returned.
+    pub fn from_config<P: AsRef<Path>>(config_file_path: P) -> Result<Self, OpenCCError> {
+        let config_file_path = config_file_path.as_ref();
+        let config_dir = config_file_path.parent().unwrap_or(Path::new(""));
+        let stem = config_file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or(OpenCCError::InvalidConfigPath)?;
+
+        let config = Json::parse(&fs::read_to_string(config_file_path)?)
+            .ok_or_else(|| invalid_config("the config is not valid JSON"))?;
+
+        let chain = config
+            .get("conversion_chain")
+            .and_then(Json::as_array)
+            .ok_or_else(|| invalid_config("the config has no conversion_chain array"))?;
+
+        if !chain.iter().any(|step| step.get("rules").is_some()) {
+            return Ok(Pipeline::new().then(stem.to_string(), OpenCC::new(config_file_path)?));
+        }
+
+        let mut pipeline = Pipeline::new();
+        let mut segment: Vec<Json> = Vec::new();
+        let mut segment_start = 0;
+
+        for (i, step) in chain.iter().enumerate() {
+            let Some(rules) = step.get("rules") else {
+                if segment.is_empty() {
+                    segment_start = i;
+                }
+
+                segment.push(step.clone());
+
+                continue;
+            };
+
+            if !segment.is_empty() {
+                let name = format!("{}#{}", stem, segment_start);
+                let opencc = open_segment(&config, config_dir, stem, mem::take(&mut segment))?;
+
+                pipeline = pipeline.then(name, opencc);
+            }
+
+            let rules = rules
+                .as_str()
+                .ok_or_else(|| invalid_config("a rules step needs a file path"))?;
+
+            pipeline = pipeline.then(
+                rules.to_string(),
+                RuleSet::from_file(config_dir.join(rules))?,
+            );
+        }
+
+        if !segment.is_empty() {
+            let name = format!("{}#{}", stem, segment_start);
+
+            pipeline = pipeline.then(name, open_segment(&config, config_dir, stem, segment)?);
+        }
+
+        Ok(pipeline)
+    }
+}
+
+fn invalid_config(message: impl Into<String>) -> OpenCCError {
+    OpenCCError::InvalidDocument {
+        format: "JSON",
+        message: message.into(),
+    }
+}
+
+/// Open the dictionary steps of a config as a handle, through a generated config in the temporary directory
+/// that refers to the dictionaries by their absolute paths. The generated config is created exclusively and
+/// removed once the handle is open.
+fn open_segment(
+    config: &Json,
+    config_dir: &Path,
+    stem: &str,
+    chain: Vec<Json>,
+) -> Result<OpenCC, OpenCCError> {
+    let mut config = config.clone();
+
+    config.set("conversion_chain", Json::Array(chain));
+    resolve_files(&mut config, config_dir);
+
+    let (temp, mut file) = TempFile::create(env::temp_dir().join(format!(
+        "{}-{}-{}.json",
+        stem,
+        process::id(),
+        GENERATED_CONFIGS.fetch_add(1, Ordering::Relaxed)
+    )))?;
+
+    file.write_all(config.to_string().as_bytes())?;
+    drop(file);
+
+    OpenCC::new(temp.path())
+}
+
+/// Make the relative `file` paths of the dictionaries that exist under `config_dir` absolute. The other
+/// paths are left for OpenCC to find in its data directory.
+fn resolve_files(value: &mut Json, config_dir: &Path) {
+    match value {
+        Json::Object(members) => {
+            for (name, value) in members.iter_mut() {
+                if name == "file"
+                    && let Json::String(file) = value
+                    && Path::new(file).is_relative()
+                {
+                    if let Ok(path) = config_dir.join(&*file).canonicalize() {
+                        *file = path.to_string_lossy().into_owned();
+                    }
+                } else {
+                    resolve_files(value, config_dir);
+                }
+            }
+        }
+        Json::Array(values) => values
+            .iter_mut()
+            .for_each(|value| resolve_files(value, config_dir)),
+        _ => (),
+    }
+}
@@ -0,0 +1,123 @@
+/*!
+Serde adapters that convert every string of a value while it is deserialized.
+
+Each module is named after a default config and can be used with `#[serde(with = "...")]` on any field whose
+type is [`Convertible`]. Serialization is left unchanged. The converters are taken from
+[`ConfigCache::global`], so they are opened once per process.
+
+```rust
+use opencc_rust_windows::serde::s2twp;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Item {
+    id: String,
+    #[serde(with = "s2twp")]
+    title: String,
+    #[serde(with = "s2twp")]
+    tags: Vec<Option<String>>,
+}
+
+let item: Item =
+    serde_json::from_str(r#"{"id": "软件", "title": "软件", "tags": ["网络", null]}"#).unwrap();
+
+assert_eq!("软件", item.id);
+assert_eq!("軟體", item.title);
+assert_eq!(vec![Some("網路".to_string()), None], item.tags);
+```
+
+Like any `with` adapter, these require the field to be present, even if its type is an `Option`. Add
+`#[serde(default)]` to fields that may be missing:
+
+```rust
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(default, with = "opencc_rust_windows::serde::s2twp")]
+    note: Option<String>,
+}
+
+let item: Item = serde_json::from_str("{}").unwrap();
+
+assert_eq!(None, item.note);
+```
+*/
+
+use ::serde::{Deserialize, Deserializer, de::Error};
+
+use crate::{ConfigCache, Convertible, DefaultConfig};
+
+/// Deserialize a value, and convert every string in it with `config`.
+///
+/// # Errors
+///
+/// If the value cannot be deserialized, the config cannot be opened or the C library conversion fails, an
+/// error is returned.
+pub fn deserialize_converted<'de, D: Deserializer<'de>, T: Deserialize<'de> + Convertible>(
+    deserializer: D,
+    config: DefaultConfig,
+) -> Result<T, D::Error> {
+    let value = T::deserialize(deserializer)?;
+
+    ConfigCache::global()
+        .get(config)
+        .and_then(|opencc| value.converted(&opencc))
+        .map_err(D::Error::custom)
+}
+
+macro_rules! config_modules {
+    ($($name:ident => $config:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Convert the strings of a field with [`DefaultConfig::", stringify!($config), "`] while it is deserialized.")]
+            pub mod $name {
+                use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+                use crate::{Convertible, DefaultConfig};
+
+                /// Deserialize a value, and convert every string in it.
+                ///
+                /// # Errors
+                ///
+                /// If the value cannot be deserialized, the config cannot be opened or the C library
+                /// conversion fails, an error is returned.
+                #[inline]
+                pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de> + Convertible>(
+                    deserializer: D,
+                ) -> Result<T, D::Error> {
+                    super::deserialize_converted(deserializer, DefaultConfig::$config)
+                }
+
+                /// Serialize a value as it is.
+                ///
+                /// # Errors
+                ///
+                /// If the value cannot be serialized, an error is returned.
+                #[inline]
+                pub fn serialize<S: Serializer, T: Serialize>(
+                    value: &T,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    value.serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+config_modules!(
+    hk2s => HK2S,
+    hk2t => HK2T,
+    jp2t => JP2T,
+    s2hk => S2HK,
+    s2t => S2T,
+    s2tw => S2TW,
+    s2twp => S2TWP,
+    t2hk => T2HK,
+    t2jp => T2JP,
+    t2s => T2S,
+    t2tw => T2TW,
+    tw2s => TW2S,
+    tw2sp => TW2SP,
+    tw2t => TW2T,
+);
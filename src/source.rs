@@ -0,0 +1,577 @@
+use std::ops::Range;
+
+use crate::{OpenCC, OpenCCError, utf8::char_len};
+
+/// The JavaScript keywords after which a `/` starts a regular expression instead of a division.
+const REGEX_KEYWORDS: [&str; 11] = [
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "yield",
+];
+
+/// Programming languages supported by [`OpenCC::convert_source`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SourceLanguage {
+    Rust,
+    C,
+    Cpp,
+    Java,
+    JavaScript,
+    TypeScript,
+    Python,
+    Go,
+}
+
+impl SourceLanguage {
+    /// Get the language of a source file from its extension, like `rs` or `tsx`, ignoring case.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Some(SourceLanguage::Rust),
+            "c" | "h" => Some(SourceLanguage::C),
+            "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" => {
+                Some(SourceLanguage::Cpp)
+            }
+            "java" => Some(SourceLanguage::Java),
+            "js" | "jsx" | "mjs" | "cjs" => Some(SourceLanguage::JavaScript),
+            "ts" | "tsx" | "mts" | "cts" => Some(SourceLanguage::TypeScript),
+            "py" | "pyi" => Some(SourceLanguage::Python),
+            "go" => Some(SourceLanguage::Go),
+            _ => None,
+        }
+    }
+
+    fn is_javascript(self) -> bool {
+        matches!(
+            self,
+            SourceLanguage::JavaScript | SourceLanguage::TypeScript
+        )
+    }
+}
+
+/// Options for [`OpenCC::convert_source`].
+#[derive(Debug, Clone)]
+pub struct SourceOptions {
+    /// Convert comments, including documentation comments. The default is `true`.
+    pub comments: bool,
+    /// Convert string literals, including Python docstrings and JavaScript template literals. The default
+    /// is `true`.
+    pub strings: bool,
+}
+
+impl Default for SourceOptions {
+    fn default() -> Self {
+        SourceOptions {
+            comments: true,
+            strings: true,
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert the comments and string literals of a source file.
+    ///
+    /// The source is tokenized well enough to tell comments and string literals apart from code, so
+    /// identifiers, character literals, regular expressions and `#include` paths are never converted.
+    /// Escape sequences in string literals and the replacement fields of Python f-strings are left as they
+    /// are, as are the expressions in JavaScript template literals.
+    ///
+    /// # Errors
+    ///
+    /// If the C library conversion fails, an error is returned.
+    pub fn convert_source<S: AsRef<str>>(
+        &self,
+        input: S,
+        language: SourceLanguage,
+        options: &SourceOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let lexer = Lexer {
+            input: input.as_bytes(),
+            language,
+            options,
+            i: 0,
+            ranges: Vec::new(),
+            previous: None,
+            previous_keyword: false,
+            braces: 0,
+            templates: Vec::new(),
+        };
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for range in lexer.run() {
+            output.push_str(&input[last..range.start]);
+            output.push_str(&self.convert_cow(&input[range.clone()])?);
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
+
+/// How backslashes behave in a string literal.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Escapes {
+    /// Escape sequences are left out of the converted text.
+    Processed,
+    /// A backslash only keeps the next character from closing the string, like in Python raw strings.
+    Raw,
+    /// Backslashes have no meaning.
+    None,
+}
+
+struct Quoted<'a> {
+    close: &'a [u8],
+    escapes: Escapes,
+    /// Whether `{...}` replacement fields are left out, like in Python f-strings.
+    fields: bool,
+    multiline: bool,
+}
+
+struct Lexer<'a> {
+    input: &'a [u8],
+    language: SourceLanguage,
+    options: &'a SourceOptions,
+    i: usize,
+    ranges: Vec<Range<usize>>,
+    /// The last significant byte of code, which tells a JavaScript regular expression from a division.
+    previous: Option<u8>,
+    previous_keyword: bool,
+    /// The depth of the open braces in code.
+    braces: usize,
+    /// The brace depths at which the expressions of open JavaScript template literals end.
+    templates: Vec<usize>,
+}
+
+impl Lexer<'_> {
+    fn run(mut self) -> Vec<Range<usize>> {
+        let language = self.language;
+        let c_style = language != SourceLanguage::Python;
+
+        while self.i < self.input.len() {
+            let b = self.input[self.i];
+            let rest = &self.input[self.i..];
+
+            if b.is_ascii_whitespace() {
+                self.i += 1;
+            } else if (c_style && rest.starts_with(b"//"))
+                || (language == SourceLanguage::Python && b == b'#')
+            {
+                let start = self.i + if c_style { 2 } else { 1 };
+                let end = self.line_end(start);
+
+                self.comment(start..end);
+                self.i = end;
+            } else if c_style && rest.starts_with(b"/*") {
+                self.block_comment();
+            } else if matches!(language, SourceLanguage::C | SourceLanguage::Cpp)
+                && b == b'#'
+                && is_include(&rest[1..])
+            {
+                self.i = self.line_end(self.i);
+            } else if b == b'"' || (b == b'\'' && language == SourceLanguage::Python) {
+                self.string_literal("");
+            } else if b == b'\'' && language.is_javascript() {
+                self.i += 1;
+                self.string(Quoted {
+                    close: b"'",
+                    escapes: Escapes::Processed,
+                    fields: false,
+                    multiline: false,
+                });
+            } else if b == b'\'' {
+                self.char_literal();
+            } else if b == b'`' && language.is_javascript() {
+                self.i += 1;
+                self.template();
+            } else if b == b'`' && language == SourceLanguage::Go {
+                self.i += 1;
+                self.string(Quoted {
+                    close: b"`",
+                    escapes: Escapes::None,
+                    fields: false,
+                    multiline: true,
+                });
+            } else if b == b'/' && language.is_javascript() && self.regex_allowed() {
+                self.regex();
+            } else if b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80 {
+                self.word();
+            } else {
+                self.i += 1;
+                self.previous = Some(b);
+                self.previous_keyword = false;
+
+                match b {
+                    b'{' => self.braces += 1,
+                    b'}' => {
+                        self.braces = self.braces.saturating_sub(1);
+
+                        if self.templates.last() == Some(&self.braces) {
+                            self.templates.pop();
+                            self.template();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.ranges
+    }
+
+    /// Read an identifier, a keyword or a number, and the string literal it is the prefix of.
+    fn word(&mut self) {
+        let start = self.i;
+
+        while let Some(&b) = self.input.get(self.i) {
+            if !(b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80) {
+                break;
+            }
+
+            self.i += 1;
+        }
+
+        // Non-ASCII identifiers are left alone, so the word is only compared when it is ASCII.
+        let word = std::str::from_utf8(&self.input[start..self.i]).unwrap_or_default();
+        let next = self.input.get(self.i).copied();
+
+        match (self.language, next) {
+            (SourceLanguage::Rust, Some(b'"' | b'#')) if matches!(word, "r" | "br" | "cr") => {
+                self.rust_raw_string();
+            }
+            (SourceLanguage::C | SourceLanguage::Cpp, Some(b'"'))
+                if matches!(word, "R" | "LR" | "uR" | "UR" | "u8R") =>
+            {
+                self.cpp_raw_string();
+            }
+            (SourceLanguage::Python, Some(b'"' | b'\''))
+                if word.len() <= 2
+                    && word
+                        .bytes()
+                        .all(|b| matches!(b.to_ascii_lowercase(), b'r' | b'b' | b'f' | b'u')) =>
+            {
+                self.string_literal(word);
+            }
+            _ => {
+                self.previous = Some(b'a');
+                self.previous_keyword = REGEX_KEYWORDS.contains(&word);
+            }
+        }
+    }
+
+    /// Read a string literal starting with a double quote, or with a single quote in Python.
+    fn string_literal(&mut self, prefix: &str) {
+        let quote = self.input[self.i];
+        let triple = [quote; 3];
+        let prefix = prefix.to_ascii_lowercase();
+
+        let python = self.language == SourceLanguage::Python;
+        let raw = python && prefix.contains('r');
+
+        let quoted = if (python || self.language == SourceLanguage::Java)
+            && self.input[self.i..].starts_with(&triple)
+        {
+            self.i += 3;
+
+            Quoted {
+                close: if quote == b'"' { b"\"\"\"" } else { b"'''" },
+                escapes: if raw {
+                    Escapes::Raw
+                } else {
+                    Escapes::Processed
+                },
+                fields: prefix.contains('f'),
+                multiline: true,
+            }
+        } else {
+            self.i += 1;
+
+            Quoted {
+                close: if quote == b'"' { b"\"" } else { b"'" },
+                escapes: if raw {
+                    Escapes::Raw
+                } else {
+                    Escapes::Processed
+                },
+                fields: prefix.contains('f'),
+                multiline: self.language == SourceLanguage::Rust,
+            }
+        };
+
+        self.string(quoted);
+    }
+
+    /// Read the rest of a string literal whose content starts at the current position.
+    fn string(&mut self, quoted: Quoted) {
+        let mut i = self.i;
+        let mut run_start = i;
+
+        while i < self.input.len() {
+            let rest = &self.input[i..];
+
+            if rest.starts_with(quoted.close) {
+                self.text(run_start..i);
+                self.i = i + quoted.close.len();
+                self.previous = Some(b'"');
+
+                return;
+            }
+
+            match rest[0] {
+                b'\\' if quoted.escapes == Escapes::Processed => {
+                    self.text(run_start..i);
+                    i += 1 + rest.get(1).map_or(0, |&b| char_len(b));
+                    run_start = i;
+                }
+                b'\\' if quoted.escapes == Escapes::Raw => {
+                    i += 1 + rest.get(1).map_or(0, |&b| char_len(b));
+                }
+                b'{' if quoted.fields && rest.get(1) == Some(&b'{') => i += 2,
+                b'{' if quoted.fields => {
+                    self.text(run_start..i);
+                    i = self.field_end(i);
+                    run_start = i;
+                }
+                b'\n' if !quoted.multiline => break,
+                _ => i += 1,
+            }
+        }
+
+        // An unterminated string ends at the end of its line or of the input.
+        let end = i.min(self.input.len());
+
+        self.text(run_start..end);
+        self.i = end;
+        self.previous = Some(b'"');
+    }
+
+    /// Read the text of a JavaScript template literal up to its end or to the next `${` expression.
+    fn template(&mut self) {
+        let mut i = self.i;
+        let mut run_start = i;
+
+        while i < self.input.len() {
+            match self.input[i] {
+                b'`' => {
+                    self.text(run_start..i);
+                    self.i = i + 1;
+                    self.previous = Some(b'"');
+
+                    return;
+                }
+                b'\\' => {
+                    self.text(run_start..i);
+                    i += 1 + self.input.get(i + 1).map_or(0, |&b| char_len(b));
+                    run_start = i;
+                }
+                b'$' if self.input.get(i + 1) == Some(&b'{') => {
+                    self.text(run_start..i);
+                    self.templates.push(self.braces);
+                    self.braces += 1;
+                    self.i = i + 2;
+                    self.previous = Some(b'{');
+
+                    return;
+                }
+                _ => i += 1,
+            }
+        }
+
+        self.text(run_start..self.input.len());
+        self.i = self.input.len();
+    }
+
+    /// Read a Rust raw string like `r#"..."#`, or a raw identifier like `r#type`.
+    fn rust_raw_string(&mut self) {
+        let hashes = self.input[self.i..]
+            .iter()
+            .take_while(|&&b| b == b'#')
+            .count();
+
+        if self.input.get(self.i + hashes) != Some(&b'"') {
+            return;
+        }
+
+        let mut close = vec![b'"'];
+        close.extend(std::iter::repeat_n(b'#', hashes));
+
+        self.i += hashes + 1;
+        self.string(Quoted {
+            close: &close,
+            escapes: Escapes::None,
+            fields: false,
+            multiline: true,
+        });
+    }
+
+    /// Read a C++ raw string like `R"delimiter(...)delimiter"`.
+    fn cpp_raw_string(&mut self) {
+        let start = self.i + 1;
+
+        let Some(open) = self.input[start..].iter().take(17).position(|&b| b == b'(') else {
+            return;
+        };
+
+        let mut close = vec![b')'];
+        close.extend_from_slice(&self.input[start..start + open]);
+        close.push(b'"');
+
+        self.i = start + open + 1;
+        self.string(Quoted {
+            close: &close,
+            escapes: Escapes::None,
+            fields: false,
+            multiline: true,
+        });
+    }
+
+    /// Skip a character literal, or a Rust lifetime.
+    fn char_literal(&mut self) {
+        let mut i = self.i + 1;
+
+        if self.language == SourceLanguage::Rust
+            && let Some(&b) = self.input.get(i)
+            && b != b'\\'
+            && self.input.get(i + char_len(b)) != Some(&b'\'')
+        {
+            // A lifetime or a label.
+            self.i = i;
+
+            return;
+        }
+
+        while let Some(&b) = self.input.get(i) {
+            match b {
+                b'\'' | b'\n' => break,
+                b'\\' => i += 2,
+                _ => i += 1,
+            }
+        }
+
+        self.i = (i + 1).min(self.input.len());
+        self.previous = Some(b'"');
+    }
+
+    /// Skip a JavaScript regular expression literal.
+    fn regex(&mut self) {
+        let mut i = self.i + 1;
+        let mut class = false;
+
+        while let Some(&b) = self.input.get(i) {
+            match b {
+                b'\\' => i += 1,
+                b'[' => class = true,
+                b']' => class = false,
+                b'/' if !class => break,
+                b'\n' => break,
+                _ => {}
+            }
+
+            i += 1;
+        }
+
+        self.i = (i + 1).min(self.input.len());
+        self.previous = Some(b'a');
+        self.previous_keyword = false;
+    }
+
+    fn regex_allowed(&self) -> bool {
+        self.previous_keyword
+            || self
+                .previous
+                .is_none_or(|b| b"(,=:[!&|?{};+-*%<>~^".contains(&b))
+    }
+
+    /// Read a block comment, which can be nested in Rust.
+    fn block_comment(&mut self) {
+        let start = self.i + 2;
+        let mut depth = 1;
+        let mut i = start;
+
+        while i < self.input.len() {
+            let rest = &self.input[i..];
+
+            if rest.starts_with(b"*/") {
+                depth -= 1;
+
+                if depth == 0 {
+                    self.comment(start..i);
+                    self.i = i + 2;
+
+                    return;
+                }
+
+                i += 2;
+            } else if rest.starts_with(b"/*") && self.language == SourceLanguage::Rust {
+                depth += 1;
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+
+        self.comment(start..self.input.len());
+        self.i = self.input.len();
+    }
+
+    /// Find the end of a Python replacement field starting at `start`, which points to its `{`.
+    fn field_end(&self, start: usize) -> usize {
+        let mut depth = 0;
+
+        for (i, &b) in self.input[start..].iter().enumerate() {
+            match b {
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return start + i + 1;
+                    }
+                }
+                b'\n' => return start + i,
+                _ => {}
+            }
+        }
+
+        self.input.len()
+    }
+
+    fn line_end(&self, from: usize) -> usize {
+        self.input[from..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(self.input.len(), |n| from + n)
+    }
+
+    fn comment(&mut self, range: Range<usize>) {
+        if self.options.comments && !range.is_empty() {
+            self.ranges.push(range);
+        }
+    }
+
+    fn text(&mut self, range: Range<usize>) {
+        if self.options.strings && !range.is_empty() {
+            self.ranges.push(range);
+        }
+    }
+}
+
+/// Whether a C preprocessor directive, without its `#`, includes a file.
+fn is_include(directive: &[u8]) -> bool {
+    let directive = directive.trim_ascii_start();
+
+    directive.starts_with(b"include") || directive.starts_with(b"import")
+}
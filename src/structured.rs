@@ -0,0 +1,707 @@
+use std::ops::Range;
+
+use crate::{OpenCC, OpenCCError};
+
+/// Options for [`OpenCC::convert_json`], [`OpenCC::convert_yaml`] and [`OpenCC::convert_toml`].
+///
+/// Values are selected with JSON pointers like `/messages` or `/items/*/title`. A pointer selects the
+/// value it points to together with everything inside it, and `*` matches any single key or index.
+#[derive(Debug, Clone, Default)]
+pub struct StructuredOptions {
+    /// Also convert the keys of objects. The default is `false`.
+    pub keys: bool,
+    /// The pointers of the converted values. The default is empty, which selects the whole document.
+    pub include: Vec<String>,
+    /// The pointers of the values that are left as they are, even inside an included value.
+    pub exclude: Vec<String>,
+}
+
+impl OpenCC {
+    /// Convert the string values of a JSON document.
+    ///
+    /// Only the contents of the selected strings are converted, and everything else is copied byte for
+    /// byte. Strings that contain `\u` escapes are decoded before the conversion, and written back with
+    /// every non-ASCII character escaped again.
+    ///
+    /// # Errors
+    ///
+    /// If the document is not valid JSON, if a pointer is invalid, or if the C library conversion fails,
+    /// an error is returned.
+    #[cfg(feature = "json")]
+    pub fn convert_json<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &StructuredOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut collector = Collector::new(options)?;
+
+        JsonScanner {
+            input,
+            i: 0,
+            collector: &mut collector,
+        }
+        .document()
+        .map_err(|message| invalid_document("JSON", message))?;
+
+        collector.apply(self, input)
+    }
+
+    /// Convert the string values of a YAML document, or of every document in a YAML stream.
+    ///
+    /// Only the source text of the selected scalars is converted, so comments, anchors, styles and
+    /// indentation are kept. Characters written as escapes in double-quoted scalars are not converted.
+    ///
+    /// # Errors
+    ///
+    /// If the input is not valid YAML, if a pointer is invalid, or if the C library conversion fails,
+    /// an error is returned.
+    #[cfg(feature = "yaml")]
+    pub fn convert_yaml<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &StructuredOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut collector = Collector::new(options)?;
+
+        yaml_edits(input, &mut collector)?;
+
+        collector.apply(self, input)
+    }
+
+    /// Convert the string values of a TOML document.
+    ///
+    /// Only the source text of the selected strings is converted, so comments and formatting are kept.
+    /// Characters written as escapes are not converted. The names of tables are never converted, because
+    /// they can be repeated in several headers and dotted keys.
+    ///
+    /// # Errors
+    ///
+    /// If the input is not valid TOML, if a pointer is invalid, or if the C library conversion fails,
+    /// an error is returned.
+    #[cfg(feature = "toml")]
+    pub fn convert_toml<S: AsRef<str>>(
+        &self,
+        input: S,
+        options: &StructuredOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let mut collector = Collector::new(options)?;
+
+        let document = toml_edit::Document::parse(input)
+            .map_err(|e| invalid_document("TOML", e.to_string()))?;
+
+        collector.toml_table(document.as_table());
+
+        collector.apply(self, input)
+    }
+}
+
+fn invalid_document(format: &'static str, message: impl Into<String>) -> OpenCCError {
+    OpenCCError::InvalidDocument {
+        format,
+        message: message.into(),
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Edit {
+    /// Convert the source text as it is.
+    #[cfg(any(feature = "yaml", feature = "toml"))]
+    Raw,
+    /// Convert the contents of a JSON string, decoding `\u` escapes.
+    #[cfg(feature = "json")]
+    JsonString,
+}
+
+/// Follows the path of the current value and collects the ranges of the selected strings.
+struct Collector {
+    keys: bool,
+    include: Vec<Vec<String>>,
+    exclude: Vec<Vec<String>>,
+    path: Vec<String>,
+    edits: Vec<(Range<usize>, Edit)>,
+}
+
+impl Collector {
+    fn new(options: &StructuredOptions) -> Result<Self, OpenCCError> {
+        let parse = |pointers: &[String]| {
+            pointers
+                .iter()
+                .map(|pointer| parse_pointer(pointer))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Collector {
+            keys: options.keys,
+            include: parse(&options.include)?,
+            exclude: parse(&options.exclude)?,
+            path: Vec::new(),
+            edits: Vec::new(),
+        })
+    }
+
+    fn is_selected(&self) -> bool {
+        let matches = |pointer: &Vec<String>| {
+            pointer.len() <= self.path.len()
+                && pointer
+                    .iter()
+                    .zip(&self.path)
+                    .all(|(segment, key)| segment == "*" || segment == key)
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Add the string at `range` if the current path is selected.
+    fn value(&mut self, range: Range<usize>, edit: Edit) {
+        if self.is_selected() {
+            self.edits.push((range, edit));
+        }
+    }
+
+    /// Add the key at `range`, which is the last segment of the current path, if keys are converted.
+    fn key(&mut self, range: Range<usize>, edit: Edit) {
+        if self.keys {
+            self.value(range, edit);
+        }
+    }
+
+    fn apply(mut self, opencc: &OpenCC, input: &str) -> Result<String, OpenCCError> {
+        self.edits.sort_by_key(|(range, _)| range.start);
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for (range, edit) in self.edits {
+            if range.start < last {
+                continue;
+            }
+
+            output.push_str(&input[last..range.start]);
+
+            let source = &input[range.clone()];
+
+            match edit {
+                #[cfg(any(feature = "yaml", feature = "toml"))]
+                Edit::Raw => output.push_str(&opencc.convert_cow(source)?),
+                #[cfg(feature = "json")]
+                Edit::JsonString => match decode_json_string(source) {
+                    Some(decoded) if source.contains("\\u") => {
+                        // An escape like `\u0000` can decode to a NULL byte, which only the byte conversion allows.
+                        let converted = String::from_utf8(
+                            opencc.convert_bytes(&decoded, crate::Utf8Mode::Strict)?,
+                        )
+                        .map_err(|_| OpenCCError::InvalidUtf8)?;
+
+                        if converted == decoded {
+                            output.push_str(source);
+                        } else {
+                            encode_json_string(&converted, &mut output);
+                        }
+                    }
+                    _ => output.push_str(&opencc.convert_cow(source)?),
+                },
+            }
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
+
+/// Split a JSON pointer into its unescaped segments.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, OpenCCError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let segments = pointer.strip_prefix('/').ok_or_else(|| {
+        invalid_document(
+            "JSON pointer",
+            format!("{:?} does not start with '/'", pointer),
+        )
+    })?;
+
+    Ok(segments
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+#[cfg(feature = "json")]
+struct JsonScanner<'a, 'c> {
+    input: &'a str,
+    i: usize,
+    collector: &'c mut Collector,
+}
+
+#[cfg(feature = "json")]
+impl JsonScanner<'_, '_> {
+    fn document(&mut self) -> Result<(), String> {
+        if self.input.starts_with('\u{feff}') {
+            self.i = 3;
+        }
+
+        self.value()?;
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected()),
+        }
+    }
+
+    fn value(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => {
+                let range = self.string()?;
+
+                self.collector.value(range, Edit::JsonString);
+
+                Ok(())
+            }
+            Some(b'-' | b'0'..=b'9' | b'a'..=b'z') => {
+                let start = self.i;
+
+                while let Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'a'..=b'z' | b'E') = self.peek()
+                {
+                    self.i += 1;
+                }
+
+                match &self.input[start..self.i] {
+                    "true" | "false" | "null" => Ok(()),
+                    number if is_json_number(number) => Ok(()),
+                    _ => {
+                        self.i = start;
+
+                        Err(self.unexpected())
+                    }
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn object(&mut self) -> Result<(), String> {
+        self.i += 1;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.i += 1;
+
+            return Ok(());
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() != Some(b'"') {
+                return Err(self.unexpected());
+            }
+
+            let range = self.string()?;
+            let key = decode_json_string(&self.input[range.clone()])
+                .ok_or_else(|| format!("invalid escape in the string at byte {}", range.start))?;
+
+            self.collector.path.push(key);
+            self.collector.key(range, Edit::JsonString);
+
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.value()?;
+
+            self.collector.path.pop();
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.i += 1,
+                Some(b'}') => {
+                    self.i += 1;
+
+                    return Ok(());
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), String> {
+        self.i += 1;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.i += 1;
+
+            return Ok(());
+        }
+
+        for index in 0.. {
+            self.collector.path.push(index.to_string());
+            self.value()?;
+            self.collector.path.pop();
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.i += 1,
+                Some(b']') => {
+                    self.i += 1;
+                    break;
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a string and return the range of its contents without the quotes.
+    fn string(&mut self) -> Result<Range<usize>, String> {
+        let start = self.i + 1;
+
+        self.i = start;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.i += 1;
+
+                    return Ok(start..self.i - 1);
+                }
+                Some(b'\\') => self.i += 2,
+                Some(_) => self.i += 1,
+                None => return Err(format!("unterminated string at byte {}", start - 1)),
+            }
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.i += 1;
+
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.i).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.i += 1;
+        }
+    }
+
+    fn unexpected(&self) -> String {
+        match self
+            .input
+            .get(self.i..)
+            .and_then(|rest| rest.chars().next())
+        {
+            Some(c) => format!("unexpected {:?} at byte {}", c, self.i),
+            None => "unexpected end of input".into(),
+        }
+    }
+}
+
+/// Check a number against the JSON grammar `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`.
+#[cfg(feature = "json")]
+fn is_json_number(number: &str) -> bool {
+    fn digits(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    let bytes = number.as_bytes();
+    let mut i = usize::from(bytes.first() == Some(&b'-'));
+
+    match bytes.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => i += digits(&bytes[i..]),
+        _ => return false,
+    }
+
+    if bytes.get(i) == Some(&b'.') {
+        let n = digits(&bytes[i + 1..]);
+
+        if n == 0 {
+            return false;
+        }
+
+        i += 1 + n;
+    }
+
+    if let Some(b'e' | b'E') = bytes.get(i) {
+        i += 1;
+
+        if let Some(b'+' | b'-') = bytes.get(i) {
+            i += 1;
+        }
+
+        let n = digits(&bytes[i..]);
+
+        if n == 0 {
+            return false;
+        }
+
+        i += n;
+    }
+
+    i == bytes.len()
+}
+
+/// Decode the contents of a JSON string, or return `None` if it has an invalid escape.
+#[cfg(feature = "json")]
+fn decode_json_string(source: &str) -> Option<String> {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars();
+
+    let hex = |chars: &mut std::str::Chars| {
+        let digits = chars.as_str().get(..4)?;
+        let value = u32::from_str_radix(digits, 16).ok()?;
+
+        chars.nth(3);
+
+        Some(value)
+    };
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '"' => output.push('"'),
+            '\\' => output.push('\\'),
+            '/' => output.push('/'),
+            'b' => output.push('\u{8}'),
+            'f' => output.push('\u{c}'),
+            'n' => output.push('\n'),
+            'r' => output.push('\r'),
+            't' => output.push('\t'),
+            'u' => {
+                let high = hex(&mut chars)?;
+
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    if !chars.as_str().starts_with("\\u") {
+                        return None;
+                    }
+
+                    chars.nth(1);
+
+                    let low = hex(&mut chars)?;
+
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return None;
+                    }
+
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+
+                output.push(char::from_u32(code)?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(output)
+}
+
+/// Encode the contents of a JSON string with every non-ASCII character escaped.
+#[cfg(feature = "json")]
+fn encode_json_string(s: &str, output: &mut String) {
+    use std::fmt::Write;
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            ' '..='~' => output.push(c),
+            _ => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(output, "\\u{:04x}", unit).unwrap();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_edits(input: &str, collector: &mut Collector) -> Result<(), OpenCCError> {
+    use saphyr_parser::{Event, Parser};
+
+    enum Node {
+        /// A mapping with the key of the current value, or `None` while a key is expected.
+        Mapping(Option<String>),
+        /// A sequence with the index of the current item.
+        Sequence(usize),
+    }
+
+    struct Frame {
+        node: Node,
+        /// Whether this collection is a part of a key, whose scalars are never converted.
+        in_key: bool,
+    }
+
+    fn path(frames: &[Frame]) -> Vec<String> {
+        frames
+            .iter()
+            .filter_map(|frame| match &frame.node {
+                Node::Mapping(key) => key.clone(),
+                Node::Sequence(index) => Some(index.to_string()),
+            })
+            .collect()
+    }
+
+    fn finish_node(frames: &mut [Frame], key: Option<String>) {
+        match frames.last_mut().map(|frame| &mut frame.node) {
+            Some(Node::Mapping(current @ None)) => *current = Some(key.unwrap_or_default()),
+            Some(Node::Mapping(current)) => *current = None,
+            Some(Node::Sequence(index)) => *index += 1,
+            None => {}
+        }
+    }
+
+    // The markers of the parser count characters, not bytes.
+    let offsets: Vec<usize> = input
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([input.len()])
+        .collect();
+    let byte = |index: usize| offsets.get(index).copied().unwrap_or(input.len());
+
+    let mut frames: Vec<Frame> = Vec::new();
+
+    for event in Parser::new_from_str(input) {
+        let (event, span) = event.map_err(|e| invalid_document("YAML", e.to_string()))?;
+
+        let is_key = matches!(
+            frames.last(),
+            Some(Frame {
+                node: Node::Mapping(None),
+                ..
+            })
+        );
+        let in_key = is_key || frames.iter().any(|frame| frame.in_key);
+
+        match event {
+            Event::Scalar(value, ..) => {
+                let range = byte(span.start.index())..byte(span.end.index());
+
+                if !frames.iter().any(|frame| frame.in_key) {
+                    collector.path = path(&frames);
+
+                    if is_key {
+                        collector.path.push(value.to_string());
+                        collector.key(range, Edit::Raw);
+                    } else {
+                        collector.value(range, Edit::Raw);
+                    }
+                }
+
+                finish_node(&mut frames, Some(value.into_owned()));
+            }
+            Event::Alias(_) => finish_node(&mut frames, None),
+            Event::MappingStart(..) => frames.push(Frame {
+                node: Node::Mapping(None),
+                in_key,
+            }),
+            Event::SequenceStart(..) => frames.push(Frame {
+                node: Node::Sequence(0),
+                in_key,
+            }),
+            Event::MappingEnd | Event::SequenceEnd => {
+                frames.pop();
+                finish_node(&mut frames, None);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "toml")]
+impl Collector {
+    fn toml_table(&mut self, table: &toml_edit::Table) {
+        for (key, item) in table.iter() {
+            self.path.push(key.to_string());
+
+            if item.is_value()
+                && !item.is_inline_table()
+                && let Some(span) = table.key(key).and_then(|key| key.span())
+            {
+                self.key(span, Edit::Raw);
+            }
+
+            match item {
+                toml_edit::Item::Value(value) => self.toml_value(value),
+                toml_edit::Item::Table(table) => self.toml_table(table),
+                toml_edit::Item::ArrayOfTables(array) => {
+                    for (index, table) in array.iter().enumerate() {
+                        self.path.push(index.to_string());
+                        self.toml_table(table);
+                        self.path.pop();
+                    }
+                }
+                toml_edit::Item::None => {}
+            }
+
+            self.path.pop();
+        }
+    }
+
+    fn toml_value(&mut self, value: &toml_edit::Value) {
+        match value {
+            toml_edit::Value::String(string) => {
+                if let Some(span) = string.span() {
+                    self.value(span, Edit::Raw);
+                }
+            }
+            toml_edit::Value::Array(array) => {
+                for (index, value) in array.iter().enumerate() {
+                    self.path.push(index.to_string());
+                    self.toml_value(value);
+                    self.path.pop();
+                }
+            }
+            toml_edit::Value::InlineTable(table) => {
+                for (key, value) in table.iter() {
+                    self.path.push(key.to_string());
+
+                    if !value.is_inline_table()
+                        && let Some(span) = table.key(key).and_then(|key| key.span())
+                    {
+                        self.key(span, Edit::Raw);
+                    }
+
+                    self.toml_value(value);
+                    self.path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use std::ops::Range;
+
+use crate::{OpenCC, OpenCCError};
+
+/// The fields of the `[V4+ Styles]` section when it has no `Format` line.
+const DEFAULT_STYLE_FIELDS: [&str; 23] = [
+    "name",
+    "fontname",
+    "fontsize",
+    "primarycolour",
+    "secondarycolour",
+    "outlinecolour",
+    "backcolour",
+    "bold",
+    "italic",
+    "underline",
+    "strikeout",
+    "scalex",
+    "scaley",
+    "spacing",
+    "angle",
+    "borderstyle",
+    "outline",
+    "shadow",
+    "alignment",
+    "marginl",
+    "marginr",
+    "marginv",
+    "encoding",
+];
+
+/// The fields of the `[Events]` section when it has no `Format` line.
+const DEFAULT_EVENT_FIELDS: [&str; 10] = [
+    "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+];
+
+/// Subtitle formats supported by [`OpenCC::convert_subtitles`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SubtitleFormat {
+    /// SubRip (`.srt`)
+    Srt,
+    /// WebVTT (`.vtt`)
+    WebVtt,
+    /// Advanced SubStation Alpha and SubStation Alpha (`.ass`, `.ssa`)
+    Ass,
+}
+
+impl SubtitleFormat {
+    /// Get the format of a subtitle file from its extension, like `srt` or `ass`, ignoring case.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" => Some(SubtitleFormat::WebVtt),
+            "ass" | "ssa" => Some(SubtitleFormat::Ass),
+            _ => None,
+        }
+    }
+}
+
+/// Options for [`OpenCC::convert_subtitles`].
+#[derive(Debug, Clone, Default)]
+pub struct SubtitleOptions {
+    /// Convert the names of ASS styles, in the style definitions, in the dialogue lines and in `\r`
+    /// override tags. The default is `false`.
+    pub style_names: bool,
+    /// Convert the font names of ASS styles and `\fn` override tags. The default is `false`, because the
+    /// converted names usually do not match any installed font.
+    pub font_names: bool,
+}
+
+impl OpenCC {
+    /// Convert the dialogue text of a subtitle file.
+    ///
+    /// Cue numbers, identifiers, timings, cue settings, markup tags, ASS override tags and the
+    /// `[Script Info]` and style sections are left as they are, and the line endings of the input are kept.
+    /// WebVTT `NOTE`, `STYLE` and `REGION` blocks are not converted.
+    ///
+    /// # Errors
+    ///
+    /// If the C library conversion fails, an error is returned.
+    pub fn convert_subtitles<S: AsRef<str>>(
+        &self,
+        input: S,
+        format: SubtitleFormat,
+        options: &SubtitleOptions,
+    ) -> Result<String, OpenCCError> {
+        let input = input.as_ref();
+
+        let ranges = match format {
+            SubtitleFormat::Srt => cue_ranges(input, &[(b'<', b'>'), (b'{', b'}')]),
+            SubtitleFormat::WebVtt => cue_ranges(input, &[(b'<', b'>')]),
+            SubtitleFormat::Ass => ass_ranges(input, options),
+        };
+
+        let mut output = String::with_capacity(input.len());
+        let mut last = 0;
+
+        for range in ranges {
+            output.push_str(&input[last..range.start]);
+            output.push_str(&self.convert_cow(&input[range.clone()])?);
+
+            last = range.end;
+        }
+
+        output.push_str(&input[last..]);
+
+        Ok(output)
+    }
+}
+
+/// Iterate over the lines of the input with their offsets, without their line endings.
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.split_inclusive('\n').scan(0, |start, line| {
+        let line_start = *start;
+
+        *start += line.len();
+
+        Some((line_start, line.trim_end_matches(['\r', '\n'])))
+    })
+}
+
+/// Collect the text ranges of SRT or WebVTT cues, which are the lines after the timing line of a block.
+fn cue_ranges(input: &str, tags: &[(u8, u8)]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut in_payload = false;
+
+    for (start, line) in lines(input) {
+        if line.trim().is_empty() {
+            in_payload = false;
+        } else if in_payload {
+            text_ranges(line, start, tags, &mut ranges);
+        } else if line.contains("-->") {
+            in_payload = true;
+        }
+    }
+
+    ranges
+}
+
+/// Collect the ranges of a line outside the tags delimited by the `tags` pairs of brackets, like `<i>`
+/// or `{\an8}`. An opening bracket without a closing one on the same line is text.
+fn text_ranges(line: &str, offset: usize, tags: &[(u8, u8)], ranges: &mut Vec<Range<usize>>) {
+    let mut text_start = 0;
+    let mut tag: Option<(usize, u8)> = None;
+
+    for (i, b) in line.bytes().enumerate() {
+        match tag {
+            None => {
+                if let Some(&(_, close)) = tags.iter().find(|(open, _)| *open == b) {
+                    push_range(ranges, offset + text_start..offset + i);
+                    tag = Some((i, close));
+                }
+            }
+            Some((_, close)) if b == close => {
+                text_start = i + 1;
+                tag = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((open, _)) = tag {
+        text_start = open;
+    }
+
+    push_range(ranges, offset + text_start..offset + line.len());
+}
+
+/// Collect the ranges of an ASS or SSA script.
+fn ass_ranges(input: &str, options: &SubtitleOptions) -> Vec<Range<usize>> {
+    #[derive(PartialEq)]
+    enum Section {
+        Styles,
+        Events,
+        Other,
+    }
+
+    let mut ranges = Vec::new();
+    let mut section = Section::Other;
+    let mut fields: Vec<String> = Vec::new();
+
+    for (start, line) in lines(input) {
+        let header = line.trim_start_matches('\u{feff}').trim();
+
+        if header.starts_with('[') {
+            let name = header.to_ascii_lowercase();
+
+            (section, fields) = if name.ends_with("styles]") {
+                (
+                    Section::Styles,
+                    DEFAULT_STYLE_FIELDS.map(String::from).to_vec(),
+                )
+            } else if name == "[events]" {
+                (
+                    Section::Events,
+                    DEFAULT_EVENT_FIELDS.map(String::from).to_vec(),
+                )
+            } else {
+                (Section::Other, Vec::new())
+            };
+
+            continue;
+        }
+
+        let Some((kind, rest)) = line.split_once(':') else {
+            continue;
+        };
+
+        let rest_start = start + kind.len() + 1;
+        let kind = kind.trim().to_ascii_lowercase();
+
+        if kind == "format" && section != Section::Other {
+            fields = rest
+                .split(',')
+                .map(|field| field.trim().to_ascii_lowercase())
+                .collect();
+
+            continue;
+        }
+
+        let values = field_ranges(rest, rest_start, fields.len());
+
+        for (field, range) in fields.iter().zip(values) {
+            match (&section, kind.as_str(), field.as_str()) {
+                (Section::Styles, "style", "name") | (Section::Events, "dialogue", "style")
+                    if options.style_names =>
+                {
+                    push_range(&mut ranges, range)
+                }
+                (Section::Styles, "style", "fontname") if options.font_names => {
+                    push_range(&mut ranges, range)
+                }
+                (Section::Events, "dialogue", "text") => {
+                    dialogue_ranges(&input[range.clone()], range.start, options, &mut ranges)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ranges
+}
+
+/// Split the comma-separated fields of a line into at most `count` ranges, the last of which takes the
+/// rest of the line. Surrounding whitespace is not a part of the ranges.
+fn field_ranges(rest: &str, offset: usize, count: usize) -> Vec<Range<usize>> {
+    let mut start = offset;
+
+    rest.splitn(count.max(1), ',')
+        .map(|field| {
+            let leading = field.len() - field.trim_start().len();
+            let range = start + leading..start + field.trim_end().len().max(leading);
+
+            start += field.len() + 1;
+
+            range
+        })
+        .collect()
+}
+
+/// Collect the ranges of the text of a dialogue line, with the style and font names in its override
+/// blocks if they are converted.
+fn dialogue_ranges(
+    text: &str,
+    offset: usize,
+    options: &SubtitleOptions,
+    ranges: &mut Vec<Range<usize>>,
+) {
+    let mut blocks = Vec::new();
+
+    text_ranges(text, offset, &[(b'{', b'}')], &mut blocks);
+
+    let mut last = 0;
+
+    for block in blocks {
+        override_ranges(
+            &text[last..block.start - offset],
+            offset + last,
+            options,
+            ranges,
+        );
+
+        push_range(ranges, block.clone());
+
+        last = block.end - offset;
+    }
+
+    override_ranges(&text[last..], offset + last, options, ranges);
+}
+
+/// Collect the ranges of the `\r` style names and `\fn` font names in the override blocks of `source`.
+fn override_ranges(
+    source: &str,
+    offset: usize,
+    options: &SubtitleOptions,
+    ranges: &mut Vec<Range<usize>>,
+) {
+    if !options.style_names && !options.font_names {
+        return;
+    }
+
+    let mut start = 0;
+
+    for tag in source.split(['\\', '{', '}']) {
+        let tag_start = offset + start;
+
+        start += tag.len() + 1;
+
+        let name = match tag.as_bytes() {
+            [b'f', b'n', ..] if options.font_names => 2,
+            [b'r', _, ..] if options.style_names => 1,
+            _ => continue,
+        };
+
+        push_range(ranges, tag_start + name..tag_start + tag.trim_end().len());
+    }
+}
+
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if !range.is_empty() {
+        ranges.push(range);
+    }
+}
@@ -0,0 +1,962 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    ffi::OsString,
+    fmt::{self, Display, Formatter},
+    fs::{self, File},
+    hash::Hash,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::{
+    Converter, OpenCCError,
+    file::{convert_file_with, file_would_change, write_atomic},
+};
+
+/// How many bytes at the start of a file are inspected to decide whether it is text.
+const TEXT_DETECTION_SIZE: usize = 8 * 1024;
+
+/// Where [`convert_tree`] writes the converted files.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TreeOutput {
+    /// Rewrite the files in place.
+    InPlace,
+    /// Mirror the tree into another directory. Files that are not converted are copied unchanged.
+    Mirror(PathBuf),
+}
+
+/// Options for [`convert_tree`].
+#[derive(Debug, Clone)]
+pub struct TreeOptions {
+    /// Globs of the files to convert, matched against paths relative to the root, like `**/*.md`.
+    /// An empty list selects every file.
+    pub include: Vec<String>,
+    /// Globs of the files and directories to leave out entirely.
+    pub exclude: Vec<String>,
+    /// Where the converted files are written.
+    pub output: TreeOutput,
+    /// Only report which files would change, without writing anything.
+    pub dry_run: bool,
+    /// Only convert files that look like UTF-8 text.
+    pub detect_text: bool,
+    /// The number of worker threads. `0` means the available parallelism of the system.
+    pub threads: usize,
+    /// Also convert the names of files and directories, and update relative links in the converted
+    /// Markdown and HTML files to point at the new names.
+    ///
+    /// A path is renamed with the converter of the config that `select` returns for it, whether or not its
+    /// content is converted. Names that would collide with each other or with an existing name are left as they are
+    /// and reported in [`TreeSummary::collisions`].
+    pub rename: bool,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        TreeOptions {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            output: TreeOutput::InPlace,
+            dry_run: false,
+            detect_text: true,
+            threads: 0,
+            rename: false,
+        }
+    }
+}
+
+/// What happened to a file in [`convert_tree`].
+#[derive(Debug)]
+pub enum FileStatus {
+    /// The file was converted and its content changed.
+    Converted,
+    /// The file was converted, but its content stayed the same.
+    Unchanged,
+    /// The file would change. Only used for dry runs.
+    WouldChange,
+    /// The file was not selected for conversion. In mirror mode, it was copied unchanged.
+    Skipped,
+    /// Converting or copying the file failed.
+    Failed(OpenCCError),
+}
+
+/// A file visited by [`convert_tree`], or a directory that could not be renamed.
+#[derive(Debug)]
+pub struct TreeEntry {
+    /// The path of the file or directory, relative to the root.
+    pub path: PathBuf,
+    /// What happened to the file.
+    pub status: FileStatus,
+    /// The new path of the file relative to the root, if it was renamed (or would be in a dry run).
+    pub renamed_to: Option<PathBuf>,
+}
+
+/// Paths that were not renamed because their converted names would be the same.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NameCollision {
+    /// The path that the names would be converted to, relative to the root.
+    pub target: PathBuf,
+    /// The paths that would share the target, relative to the root.
+    pub sources: Vec<PathBuf>,
+}
+
+/// The result of [`convert_tree`]. Its `Display` implementation prints a one-line summary.
+#[derive(Debug, Default)]
+pub struct TreeSummary {
+    /// Every visited file and every directory that could not be renamed, sorted by path.
+    pub entries: Vec<TreeEntry>,
+    /// Every renamed directory as pairs of the old and the new path relative to the root.
+    pub renamed_dirs: Vec<(PathBuf, PathBuf)>,
+    /// Names that were not converted because of collisions.
+    pub collisions: Vec<NameCollision>,
+}
+
+impl TreeSummary {
+    /// Iterate over the entries with a given status.
+    pub fn with_status<'a>(
+        &'a self,
+        filter: impl Fn(&FileStatus) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a TreeEntry> + 'a {
+        self.entries.iter().filter(move |e| filter(&e.status))
+    }
+
+    /// Iterate over the files that changed, or would change in a dry run.
+    pub fn changed(&self) -> impl Iterator<Item = &TreeEntry> {
+        self.with_status(|s| matches!(s, FileStatus::Converted | FileStatus::WouldChange))
+    }
+
+    /// Iterate over the files that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &TreeEntry> {
+        self.with_status(|s| matches!(s, FileStatus::Failed(_)))
+    }
+
+    fn count(&self, filter: impl Fn(&FileStatus) -> bool) -> usize {
+        self.entries.iter().filter(|e| filter(&e.status)).count()
+    }
+}
+
+impl Display for TreeSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let would_change = self.count(|s| matches!(s, FileStatus::WouldChange));
+
+        if would_change > 0 {
+            write!(f, "{} would change, ", would_change)?;
+        } else {
+            write!(
+                f,
+                "{} converted, ",
+                self.count(|s| matches!(s, FileStatus::Converted))
+            )?;
+        }
+
+        write!(
+            f,
+            "{} unchanged, {} skipped, {} failed",
+            self.count(|s| matches!(s, FileStatus::Unchanged)),
+            self.count(|s| matches!(s, FileStatus::Skipped)),
+            self.count(|s| matches!(s, FileStatus::Failed(_))),
+        )?;
+
+        // Paths that only moved because a parent directory was renamed are not counted.
+        let renamed = self
+            .entries
+            .iter()
+            .filter_map(|e| e.renamed_to.as_ref().map(|n| (&e.path, n)))
+            .chain(self.renamed_dirs.iter().map(|(o, n)| (o, n)))
+            .filter(|(o, n)| o.file_name() != n.file_name())
+            .count();
+
+        if renamed > 0 {
+            write!(f, ", {} renamed", renamed)?;
+        }
+
+        if !self.collisions.is_empty() {
+            write!(f, ", {} name collisions", self.collisions.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert every selected file in a directory tree.
+///
+/// A file is converted if it matches `options.include`, does not match `options.exclude`, looks like text
+/// (when `options.detect_text` is set), and `select` returns a config for its relative path. This allows
+/// choosing a different config for every file. A config is any key, like a [`DefaultConfig`](crate::DefaultConfig)
+/// or the path of a config file, and `open` creates the converter of a config.
+///
+/// The files are processed in parallel. An OpenCC handle converts one text at a time, so every worker
+/// thread opens its own converter for each config it needs, instead of sharing one.
+///
+/// The link targets of Markdown and HTML files are not converted, so links keep pointing at files whose names
+/// did not change. With `options.rename`, the links to renamed paths are rewritten to the new names.
+///
+/// ```rust,no_run
+/// use opencc_rust_windows::{DefaultConfig, OpenCC, TreeOptions, convert_tree};
+///
+/// let summary = convert_tree(
+///     "docs",
+///     &TreeOptions::default(),
+///     |_| Some(DefaultConfig::S2TWP),
+///     |&config| OpenCC::from_default_config(config),
+/// )
+/// .unwrap();
+///
+/// println!("{}", summary);
+/// ```
+///
+/// Errors of single files, including configs that cannot be opened, are recorded in the summary instead of
+/// stopping the whole run.
+///
+/// # Errors
+///
+/// If a glob is invalid or the tree cannot be walked, an error is returned.
+pub fn convert_tree<P, K, C, F, O>(
+    root: P,
+    options: &TreeOptions,
+    select: F,
+    open: O,
+) -> Result<TreeSummary, OpenCCError>
+where
+    P: AsRef<Path>,
+    K: Eq + Hash,
+    C: Converter,
+    F: Fn(&Path) -> Option<K> + Sync,
+    O: Fn(&K) -> Result<C, OpenCCError> + Sync,
+{
+    let root = root.as_ref();
+
+    let include = if options.include.is_empty() {
+        build_glob_set(&["**".to_string()])?
+    } else {
+        build_glob_set(&options.include)?
+    };
+    let exclude = build_glob_set(&options.exclude)?;
+
+    let skip_dir = match &options.output {
+        TreeOutput::Mirror(output) => fs::canonicalize(output).ok(),
+        TreeOutput::InPlace => None,
+    };
+
+    let mut tree = Tree::default();
+    walk(
+        root,
+        Path::new(""),
+        &exclude,
+        skip_dir.as_deref(),
+        &mut tree,
+    )?;
+
+    let mut renames = Renames::default();
+
+    if options.rename {
+        plan_renames(
+            Path::new(""),
+            Path::new(""),
+            &tree,
+            &select,
+            &mut Converters::new(&open),
+            &mut renames,
+        )?;
+    }
+
+    let files = &tree.files;
+
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(files.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let entries = Mutex::new(Vec::with_capacity(files.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut converters = Converters::new(&open);
+
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+
+                    let Some(path) = files.get(index) else {
+                        break;
+                    };
+
+                    let status = process_file(
+                        root,
+                        path,
+                        options,
+                        &include,
+                        &renames,
+                        &select,
+                        &mut converters,
+                    )
+                    .unwrap_or_else(FileStatus::Failed);
+
+                    entries.lock().unwrap().push(TreeEntry {
+                        path: path.clone(),
+                        status,
+                        renamed_to: renames.renamed(path).map(Path::to_path_buf),
+                    });
+                }
+            });
+        }
+    });
+
+    let mut entries = entries.into_inner().unwrap();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut renamed_dirs: Vec<_> = tree
+        .dirs
+        .iter()
+        .filter_map(|d| renames.renamed(d).map(|n| (d.clone(), n.to_path_buf())))
+        .collect();
+
+    if options.output == TreeOutput::InPlace && !options.dry_run {
+        for entry in entries.iter_mut() {
+            if let Some(new_path) = &entry.renamed_to
+                && let Err(e) = rename_last_component(root, &entry.path, new_path)
+            {
+                entry.renamed_to = None;
+                entry.status = FileStatus::Failed(e.into());
+            }
+        }
+
+        // Rename the deepest directories first, so their parents still have the old names.
+        renamed_dirs.sort_by_key(|(d, _)| std::cmp::Reverse(d.components().count()));
+
+        let mut failed_dirs = Vec::new();
+
+        for (old_path, new_path) in &renamed_dirs {
+            if let Err(e) = rename_last_component(root, old_path, new_path) {
+                failed_dirs.push((old_path.clone(), new_path.clone(), e));
+            }
+        }
+
+        // The paths below a directory that could not be renamed keep its old name.
+        for (old_path, new_path, e) in failed_dirs {
+            let kept_path = new_path.with_file_name(old_path.file_name().unwrap_or_default());
+
+            let keep_old_name = |path: &mut PathBuf| {
+                if let Ok(rest) = path.strip_prefix(&new_path) {
+                    *path = kept_path.join(rest);
+                }
+            };
+
+            for entry in entries.iter_mut() {
+                if let Some(renamed_to) = &mut entry.renamed_to {
+                    keep_old_name(renamed_to);
+
+                    if *renamed_to == entry.path {
+                        entry.renamed_to = None;
+                    }
+                }
+            }
+
+            renamed_dirs.retain(|(o, _)| *o != old_path);
+
+            for (_, n) in renamed_dirs.iter_mut() {
+                keep_old_name(n);
+            }
+
+            entries.push(TreeEntry {
+                path: old_path,
+                status: FileStatus::Failed(e.into()),
+                renamed_to: None,
+            });
+        }
+
+        renamed_dirs.retain(|(o, n)| o != n);
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    renamed_dirs.sort();
+
+    Ok(TreeSummary {
+        entries,
+        renamed_dirs,
+        collisions: renames.collisions,
+    })
+}
+
+/// The converters opened by one thread, by their configs.
+struct Converters<'o, K, C, O> {
+    open: &'o O,
+    converters: HashMap<K, C>,
+}
+
+impl<'o, K, C, O> Converters<'o, K, C, O>
+where
+    K: Eq + Hash,
+    O: Fn(&K) -> Result<C, OpenCCError>,
+{
+    fn new(open: &'o O) -> Self {
+        Converters {
+            open,
+            converters: HashMap::new(),
+        }
+    }
+
+    /// Get the converter of `config`, opening it the first time.
+    fn get(&mut self, config: K) -> Result<&C, OpenCCError> {
+        Ok(match self.converters.entry(config) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let converter = (self.open)(entry.key())?;
+
+                entry.insert(converter)
+            }
+        })
+    }
+}
+
+fn process_file<K, C, F, O>(
+    root: &Path,
+    relative: &Path,
+    options: &TreeOptions,
+    include: &GlobSet,
+    renames: &Renames,
+    select: &F,
+    converters: &mut Converters<K, C, O>,
+) -> Result<FileStatus, OpenCCError>
+where
+    K: Eq + Hash,
+    C: Converter,
+    F: Fn(&Path) -> Option<K>,
+    O: Fn(&K) -> Result<C, OpenCCError>,
+{
+    let src = root.join(relative);
+    let new_relative = renames.renamed(relative).unwrap_or(relative);
+
+    let config = if include.is_match(relative) && (!options.detect_text || is_text_file(&src)?) {
+        select(relative)
+    } else {
+        None
+    };
+
+    let Some(config) = config else {
+        if let TreeOutput::Mirror(output) = &options.output
+            && !options.dry_run
+        {
+            let dst = output.join(new_relative);
+
+            create_parent_dir(&dst)?;
+            fs::copy(&src, &dst)?;
+        }
+
+        return Ok(FileStatus::Skipped);
+    };
+
+    let converter = converters.get(config)?;
+
+    if has_links(relative) {
+        let content = fs::read_to_string(&src)?;
+        let converted = convert_document(converter, &content, relative, new_relative, renames)?;
+        let changed = converted != content;
+
+        if options.dry_run {
+            return Ok(if changed {
+                FileStatus::WouldChange
+            } else {
+                FileStatus::Unchanged
+            });
+        }
+
+        let dst = match &options.output {
+            TreeOutput::InPlace => src,
+            TreeOutput::Mirror(output) => {
+                let dst = output.join(new_relative);
+
+                // Copying first keeps the permissions of the source file.
+                create_parent_dir(&dst)?;
+                fs::copy(&src, &dst)?;
+
+                dst
+            }
+        };
+
+        if changed {
+            write_atomic(&dst, converted.as_bytes())?;
+        }
+
+        return Ok(if changed {
+            FileStatus::Converted
+        } else {
+            FileStatus::Unchanged
+        });
+    }
+
+    if options.dry_run {
+        return Ok(if file_would_change(converter, &src)? {
+            FileStatus::WouldChange
+        } else {
+            FileStatus::Unchanged
+        });
+    }
+
+    let changed = match &options.output {
+        TreeOutput::InPlace => convert_file_with(converter, &src, &src, None, true)?,
+        TreeOutput::Mirror(output) => {
+            let dst = output.join(new_relative);
+
+            create_parent_dir(&dst)?;
+            convert_file_with(converter, &src, &dst, None, false)?
+        }
+    };
+
+    Ok(if changed {
+        FileStatus::Converted
+    } else {
+        FileStatus::Unchanged
+    })
+}
+
+/// The walked part of a tree. All paths are relative to the root.
+#[derive(Debug, Default)]
+struct Tree {
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+    /// The names of every entry of every walked directory, including excluded ones, and whether they were walked.
+    listing: HashMap<PathBuf, Vec<(OsString, bool)>>,
+}
+
+fn walk(
+    root: &Path,
+    relative: &Path,
+    exclude: &GlobSet,
+    skip_dir: Option<&Path>,
+    tree: &mut Tree,
+) -> Result<(), OpenCCError> {
+    let directory = root.join(relative);
+    let mut listing = Vec::new();
+
+    for entry in fs::read_dir(&directory)? {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+
+        if exclude.is_match(&path) {
+            listing.push((entry.file_name(), false));
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            if let Some(skip_dir) = skip_dir
+                && fs::canonicalize(root.join(&path))? == skip_dir
+            {
+                listing.push((entry.file_name(), false));
+                continue;
+            }
+
+            listing.push((entry.file_name(), true));
+            walk(root, &path, exclude, skip_dir, tree)?;
+            tree.dirs.push(path);
+        } else if file_type.is_file() {
+            listing.push((entry.file_name(), true));
+            tree.files.push(path);
+        } else {
+            listing.push((entry.file_name(), false));
+        }
+    }
+
+    tree.listing.insert(relative.to_path_buf(), listing);
+
+    Ok(())
+}
+
+/// The planned renames of a tree.
+#[derive(Debug, Default)]
+struct Renames {
+    /// Old paths mapped to new paths, for every renamed file and directory and every file or directory inside a renamed directory.
+    paths: HashMap<PathBuf, PathBuf>,
+    collisions: Vec<NameCollision>,
+}
+
+impl Renames {
+    fn renamed(&self, path: &Path) -> Option<&Path> {
+        self.paths.get(path).map(PathBuf::as_path)
+    }
+}
+
+/// Decide the new names of the entries in `old_dir`, whose new path is `new_dir`, and of everything below it.
+fn plan_renames<K, C, F, O>(
+    old_dir: &Path,
+    new_dir: &Path,
+    tree: &Tree,
+    select: &F,
+    converters: &mut Converters<K, C, O>,
+    renames: &mut Renames,
+) -> Result<(), OpenCCError>
+where
+    K: Eq + Hash,
+    C: Converter,
+    F: Fn(&Path) -> Option<K>,
+    O: Fn(&K) -> Result<C, OpenCCError>,
+{
+    let Some(listing) = tree.listing.get(old_dir) else {
+        return Ok(());
+    };
+
+    let mut names = Vec::with_capacity(listing.len());
+
+    for (name, walked) in listing {
+        let converted = match (walked, name.to_str()) {
+            (true, Some(name_str)) => match select(&old_dir.join(name)) {
+                Some(config) => OsString::from(converters.get(config)?.convert(name_str)?),
+                None => name.clone(),
+            },
+            _ => name.clone(),
+        };
+
+        names.push(converted);
+    }
+
+    // Keep the original names of all entries that would end up with the same name, until nothing collides.
+    loop {
+        let mut groups: HashMap<&OsString, Vec<usize>> = HashMap::new();
+
+        for (i, name) in names.iter().enumerate() {
+            groups.entry(name).or_default().push(i);
+        }
+
+        let mut collided: Vec<Vec<usize>> = groups
+            .into_values()
+            .filter(|g| g.len() > 1 && g.iter().any(|&i| names[i] != listing[i].0))
+            .collect();
+
+        if collided.is_empty() {
+            break;
+        }
+
+        collided.sort();
+
+        for group in collided {
+            renames.collisions.push(NameCollision {
+                target: old_dir.join(&names[group[0]]),
+                sources: group.iter().map(|&i| old_dir.join(&listing[i].0)).collect(),
+            });
+
+            for i in group {
+                names[i] = listing[i].0.clone();
+            }
+        }
+    }
+
+    for ((name, walked), new_name) in listing.iter().zip(names) {
+        if !walked {
+            continue;
+        }
+
+        let old_path = old_dir.join(name);
+        let new_path = new_dir.join(new_name);
+
+        if old_path != new_path {
+            renames.paths.insert(old_path.clone(), new_path.clone());
+        }
+
+        plan_renames(&old_path, &new_path, tree, select, converters, renames)?;
+    }
+
+    Ok(())
+}
+
+/// Rename the last component of `old_path` to the last component of `new_path`, in the directory of `old_path`.
+fn rename_last_component(root: &Path, old_path: &Path, new_path: &Path) -> io::Result<()> {
+    let (Some(old_name), Some(new_name)) = (old_path.file_name(), new_path.file_name()) else {
+        return Ok(());
+    };
+
+    if old_name == new_name {
+        return Ok(());
+    }
+
+    let old_full = root.join(old_path);
+    let new_full = old_full.with_file_name(new_name);
+
+    if new_full.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists", new_full.display()),
+        ));
+    }
+
+    fs::rename(old_full, new_full)
+}
+
+fn has_links(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref(),
+        Some("md" | "markdown" | "html" | "htm" | "xhtml")
+    )
+}
+
+/// Convert a Markdown or HTML document without converting its link targets. Relative links that point at
+/// renamed paths are rewritten to the new paths, and every other link is kept as it is.
+fn convert_document<C: Converter>(
+    converter: &C,
+    content: &str,
+    old_path: &Path,
+    new_path: &Path,
+    renames: &Renames,
+) -> Result<String, OpenCCError> {
+    let old_dir = old_path.parent().unwrap_or(Path::new(""));
+    let new_dir = new_path.parent().unwrap_or(Path::new(""));
+
+    let mut output = String::with_capacity(content.len());
+    let mut last = 0;
+
+    for (start, end) in link_targets(content) {
+        // Skip targets overlapping one that was already kept.
+        if start < last {
+            continue;
+        }
+
+        if start > last {
+            converter.convert_append(&content[last..start], &mut output)?;
+        }
+
+        let target = &content[start..end];
+
+        match update_link(target, old_dir, new_dir, renames) {
+            Some(updated) => output.push_str(&updated),
+            None => output.push_str(target),
+        }
+
+        last = end;
+    }
+
+    if last < content.len() {
+        converter.convert_append(&content[last..], &mut output)?;
+    }
+
+    Ok(output)
+}
+
+fn update_link(target: &str, old_dir: &Path, new_dir: &Path, renames: &Renames) -> Option<String> {
+    if target.is_empty()
+        || target.starts_with(['/', '#', '?'])
+        || target
+            .split_once(':')
+            .is_some_and(|(scheme, _)| !scheme.contains(['/', '.', '#', '?']))
+    {
+        return None;
+    }
+
+    let split = target.find(['#', '?']).unwrap_or(target.len());
+    let (path, suffix) = target.split_at(split);
+    let encoded = path.contains('%');
+    let decoded = if encoded {
+        percent_decode(path)?
+    } else {
+        path.to_string()
+    };
+
+    let mut resolved = old_dir.to_path_buf();
+
+    for component in decoded.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            c => resolved.push(c),
+        }
+    }
+
+    let renamed = renames.renamed(&resolved)?;
+
+    let mut link = relative_link(new_dir, renamed);
+
+    if decoded.ends_with('/') {
+        link.push('/');
+    }
+
+    if encoded {
+        link = percent_encode(&link);
+    }
+
+    link.push_str(suffix);
+
+    Some(link)
+}
+
+/// Find the byte ranges of link targets: Markdown inline links and reference definitions, and HTML `href` and `src` attributes.
+fn link_targets(content: &str) -> Vec<(usize, usize)> {
+    let bytes = content.as_bytes();
+    let mut targets = Vec::new();
+
+    for (i, _) in content.match_indices("](") {
+        let mut start = i + 2;
+
+        let end = if bytes.get(start) == Some(&b'<') {
+            start += 1;
+            content[start..].find('>').map(|n| start + n)
+        } else {
+            content[start..]
+                .find([' ', ')', '\n', '\t'])
+                .map(|n| start + n)
+        };
+
+        if let Some(end) = end {
+            targets.push((start, end));
+        }
+    }
+
+    for line_start in std::iter::once(0).chain(content.match_indices('\n').map(|(i, _)| i + 1)) {
+        let line = &content[line_start..];
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        if indent > 3 || !trimmed.starts_with('[') {
+            continue;
+        }
+
+        let Some(close) = trimmed.find("]:") else {
+            continue;
+        };
+
+        if trimmed[..close].contains('\n') {
+            continue;
+        }
+
+        let rest = &trimmed[close + 2..];
+        let start = line_start
+            + indent
+            + close
+            + 2
+            + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+        let end = content[start..]
+            .find([' ', '\t', '\n', '\r'])
+            .map_or(content.len(), |n| start + n);
+
+        if end > start {
+            targets.push((start, end));
+        }
+    }
+
+    let lowercase = content.to_ascii_lowercase();
+
+    for attribute in ["href=", "src="] {
+        for (i, _) in lowercase.match_indices(attribute) {
+            if i > 0 && !bytes[i - 1].is_ascii_whitespace() {
+                continue;
+            }
+
+            let start = i + attribute.len();
+
+            let Some(&quote @ (b'"' | b'\'')) = bytes.get(start) else {
+                continue;
+            };
+
+            if let Some(n) = content[start + 1..].find(quote as char) {
+                targets.push((start + 1, start + 1 + n));
+            }
+        }
+    }
+
+    targets.sort();
+    targets.dedup();
+
+    targets
+}
+
+/// Build a link with `/` separators from the directory `from` to the path `to`, both relative to the root.
+fn relative_link(from: &Path, to: &Path) -> String {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+
+    parts.join("/")
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            output.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            output.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(output).ok()
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for &b in s.as_bytes() {
+        if b.is_ascii_graphic() && b != b'%' {
+            output.push(b as char);
+        } else {
+            output.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    output
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, OpenCCError> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| OpenCCError::InvalidGlob(e.to_string()))?);
+    }
+
+    builder
+        .build()
+        .map_err(|e| OpenCCError::InvalidGlob(e.to_string()))
+}
+
+/// Check whether a file starts with valid UTF-8 and contains no NULL bytes in that part.
+fn is_text_file(path: &Path) -> io::Result<bool> {
+    let mut buffer = Vec::with_capacity(TEXT_DETECTION_SIZE);
+
+    File::open(path)?
+        .take(TEXT_DETECTION_SIZE as u64)
+        .read_to_end(&mut buffer)?;
+
+    if buffer.contains(&0) {
+        return Ok(false);
+    }
+
+    Ok(match std::str::from_utf8(&buffer) {
+        Ok(_) => true,
+        // The inspected part may end in the middle of a character.
+        Err(e) => e.error_len().is_none(),
+    })
+}
+
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
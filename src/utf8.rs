@@ -0,0 +1,9 @@
+/// Get the length of a UTF-8 sequence from its first byte.
+pub(crate) fn char_len(first: u8) -> usize {
+    match first {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        0xC0.. => 2,
+        _ => 1,
+    }
+}
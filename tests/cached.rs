@@ -0,0 +1,86 @@
+#![cfg(feature = "cache")]
+
+use std::{fs, path::PathBuf, sync::Arc, thread};
+
+use opencc_rust_windows::{CacheStats, CachedOpenCC, DefaultConfig, OpenCC};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn hits_misses_and_eviction() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+
+    // Each entry of a two-character input is 12 bytes, so the cache holds two of them.
+    let opencc = Arc::new(CachedOpenCC::new(config_path, 30).unwrap());
+
+    assert_eq!("軟體", opencc.convert("软件").unwrap());
+    assert_eq!("網路", opencc.convert("网络").unwrap());
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let opencc = Arc::clone(&opencc);
+
+            thread::spawn(move || opencc.convert("软件").unwrap())
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!("軟體", handle.join().unwrap());
+    }
+
+    // `网络` is the least recently used entry, so it is evicted.
+    assert_eq!("設計", opencc.convert("设计").unwrap());
+    assert_eq!("軟體", opencc.convert("软件").unwrap());
+
+    assert_eq!(
+        CacheStats {
+            hits: 5,
+            misses: 3,
+            evictions: 1,
+            entries: 2,
+            size: 24,
+            capacity: 30,
+        },
+        opencc.stats()
+    );
+
+    // An entry larger than the capacity is not cached.
+    assert_eq!("這個軟體網路", opencc.convert("这个软件网络").unwrap());
+    assert_eq!(2, opencc.stats().entries);
+}
+
+#[test]
+fn invalidated_on_reload() {
+    // Copy the configs and their dictionaries, so the config can be removed.
+    let dir = tempfile::tempdir().unwrap();
+
+    for entry in fs::read_dir(get_config_path(DefaultConfig::S2TWP).parent().unwrap()).unwrap() {
+        let entry = entry.unwrap();
+
+        fs::copy(entry.path(), dir.path().join(entry.file_name())).unwrap();
+    }
+
+    let config_path = dir.path().join(DefaultConfig::S2TWP.get_file_name());
+
+    let opencc = CachedOpenCC::new(&config_path, 1024).unwrap();
+
+    assert_eq!("軟體", opencc.convert("软件").unwrap());
+    assert_eq!(1, opencc.stats().entries);
+
+    opencc.reload().unwrap();
+    assert_eq!(0, opencc.stats().entries);
+
+    opencc.replace(OpenCC::new(get_config_path(DefaultConfig::S2HK)).unwrap());
+    assert_eq!("軟件", opencc.convert("软件").unwrap());
+    assert_eq!(2, opencc.stats().misses);
+
+    fs::remove_file(&config_path).unwrap();
+    assert!(opencc.reload().is_err());
+    assert_eq!("軟件", opencc.convert("软件").unwrap());
+    assert_eq!(1, opencc.stats().hits);
+}
@@ -0,0 +1,231 @@
+#![cfg(feature = "catalog")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{CatalogFormat, CatalogOptions, DefaultConfig, OpenCC, OpenCCError};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn po_and_fluent() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let options = CatalogOptions {
+        mark_changed: true,
+        ..CatalogOptions::for_config(DefaultConfig::S2TWP)
+    };
+
+    let input = r#"msgid ""
+msgstr ""
+"Language: zh_CN\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: src/main.c:1
+#, c-format
+msgid "This %s"
+msgstr "这个软件 %s {软件}"
+
+msgid "{count, plural, one {# file} other {# files}}"
+msgstr ""
+"{count, plural, one {# 个软件} "
+"other {# 个网络}}"
+
+#, fuzzy
+msgid "Design"
+msgstr "设计\n"
+
+msgid "OK"
+msgstr "OK"
+
+msgid "Network software"
+msgstr ""
+"网络软"
+"件"
+
+#~ msgid "Old"
+#~ msgstr "软件"
+"#;
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::Po, &options)
+        .unwrap();
+
+    assert_eq!(
+        r#"msgid ""
+msgstr ""
+"Language: zh_TW\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: src/main.c:1
+#, c-format, fuzzy
+msgid "This %s"
+msgstr "這個軟體 %s {软件}"
+
+#, fuzzy
+msgid "{count, plural, one {# file} other {# files}}"
+msgstr ""
+"{count, plural, one {# 個軟體} "
+"other {# 個網路}}"
+
+#, fuzzy
+msgid "Design"
+msgstr "設計\n"
+
+msgid "OK"
+msgstr "OK"
+
+#, fuzzy
+msgid "Network software"
+msgstr ""
+"網路軟"
+"體"
+
+#~ msgid "Old"
+#~ msgstr "软件"
+"#,
+        output
+    );
+
+    let input = "# 软件\n\
+                 -brand = 软件\n\
+                 app-title = 这个{ -brand }软件\n    网络\n    .title = 标题 { $name }\n\
+                 files = { $count ->\n        [one] 一个软件\n       *[other] { $count } 个软件\n    }\n";
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::Fluent, &options)
+        .unwrap();
+
+    assert_eq!(
+        "# 软件\n\
+         -brand = 軟體\n\
+         app-title = 這個{ -brand }軟體\n    網路\n    .title = 標題 { $name }\n\
+         files = { $count ->\n        [one] 一個軟體\n       *[other] { $count } 個軟體\n    }\n",
+        output
+    );
+}
+
+#[test]
+fn android_and_apple_strings() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let options = CatalogOptions::for_config(DefaultConfig::S2TWP);
+
+    let input = r#"<resources>
+    <!-- 软件 -->
+    <string name="app_name">这个软件</string>
+    <string name="key" translatable="false">软件</string>
+    <string name="count">%1$d 个<xliff:g id="n">软件</xliff:g><b>网络</b>\'软件\'</string>
+    <plurals name="files"><item quantity="other">%d 个软件</item></plurals>
+    <style name="Theme"><item name="android:text">软件</item></style>
+</resources>
+"#;
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::AndroidStrings, &options)
+        .unwrap();
+
+    assert_eq!(
+        r#"<resources>
+    <!-- 软件 -->
+    <string name="app_name">這個軟體</string>
+    <string name="key" translatable="false">软件</string>
+    <string name="count">%1$d 個<xliff:g id="n">软件</xliff:g><b>網路</b>\'軟體\'</string>
+    <plurals name="files"><item quantity="other">%d 個軟體</item></plurals>
+    <style name="Theme"><item name="android:text">软件</item></style>
+</resources>
+"#,
+        output
+    );
+
+    let input = "/* 软件 */\n\"软件\" = \"这个软件\\n%@\";\n// 网络\n\"key\" = \"网络\";\n";
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::AppleStrings, &options)
+        .unwrap();
+
+    assert_eq!(
+        "/* 软件 */\n\"软件\" = \"這個軟體\\n%@\";\n// 网络\n\"key\" = \"網路\";\n",
+        output
+    );
+
+    assert_eq!(
+        Some(CatalogFormat::Xliff),
+        CatalogFormat::from_extension("XLF")
+    );
+}
+
+#[test]
+fn xliff_and_arb() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let options = CatalogOptions {
+        mark_changed: true,
+        ..CatalogOptions::for_config(DefaultConfig::S2TWP)
+    };
+
+    let input = r#"<xliff version="1.2"><file source-language="en" target-language="zh-CN"><body>
+<trans-unit id="1"><source>Software</source><target>这个软件<ph id="1">软件</ph></target></trans-unit>
+<trans-unit id="2"><source>OK</source><target state="translated">OK</target></trans-unit>
+<trans-unit id="3"><source>Network</source><target state="translated"><g id="b">网络</g></target></trans-unit>
+</body></file></xliff>"#;
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::Xliff, &options)
+        .unwrap();
+
+    assert_eq!(
+        r#"<xliff version="1.2"><file source-language="en" target-language="zh-TW"><body>
+<trans-unit id="1"><source>Software</source><target state="needs-review-translation">這個軟體<ph id="1">软件</ph></target></trans-unit>
+<trans-unit id="2"><source>OK</source><target state="translated">OK</target></trans-unit>
+<trans-unit id="3"><source>Network</source><target state="needs-review-translation"><g id="b">網路</g></target></trans-unit>
+</body></file></xliff>"#,
+        output
+    );
+
+    let input = r#"<xliff version="2.0" srcLang="en" trgLang="zh-CN"><file id="f"><unit id="1"><segment state="initial"><source>Software</source><target>软件</target></segment></unit></file></xliff>"#;
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::Xliff, &options)
+        .unwrap();
+
+    assert_eq!(
+        r#"<xliff version="2.0" srcLang="en" trgLang="zh-TW"><file id="f"><unit id="1"><segment state="translated"><source>Software</source><target>軟體</target></segment></unit></file></xliff>"#,
+        output
+    );
+
+    let input = r#"{
+  "@@locale": "zh_CN",
+  "title": "这个软件",
+  "@title": {"description": "软件", "placeholders": {}},
+  "count": "{count, plural, =0{软件} other{{count} 个软件}}",
+  "key\"x": "网络"
+}"#;
+
+    let output = opencc
+        .convert_catalog(input, CatalogFormat::Arb, &options)
+        .unwrap();
+
+    assert_eq!(
+        r#"{
+  "@@locale": "zh_TW",
+  "title": "這個軟體",
+  "@title": {"description": "软件", "placeholders": {}},
+  "count": "{count, plural, =0{軟體} other{{count} 個軟體}}",
+  "key\"x": "網路"
+}"#,
+        output
+    );
+
+    assert!(matches!(
+        opencc.convert_catalog("[]", CatalogFormat::Arb, &options),
+        Err(OpenCCError::InvalidDocument { format: "ARB", .. })
+    ));
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use tempfile::tempdir;
+
+const BIN: &str = env!("CARGO_BIN_EXE_opencc-rs");
+
+#[test]
+fn stdin_to_stdout() {
+    let mut child = Command::new(BIN)
+        .args(["--config", "s2twp"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all("凉风有讯\n秋月无边".as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        "涼風有訊\n秋月無邊",
+        String::from_utf8(output.stdout).unwrap()
+    );
+}
+
+#[test]
+fn in_place_and_output_dir() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    let tree = dir.path().join("docs");
+    let output = dir.path().join("out");
+
+    fs::create_dir_all(tree.join("sub")).unwrap();
+    fs::write(&file, "凉风有讯").unwrap();
+    fs::write(tree.join("sub/b.md"), "秋月无边").unwrap();
+
+    let status = Command::new(BIN)
+        .args(["-c", "S2TWP.json", "-i", "--backup", ".orig"])
+        .arg(&file)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!("涼風有訊", fs::read_to_string(&file).unwrap());
+    assert_eq!(
+        "凉风有讯",
+        fs::read_to_string(dir.path().join("a.txt.orig")).unwrap()
+    );
+
+    let status = Command::new(BIN)
+        .args(["-c", "s2twp", "-d"])
+        .arg(&output)
+        .arg(&tree)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!(
+        "秋月無邊",
+        fs::read_to_string(output.join("docs/sub/b.md")).unwrap()
+    );
+    assert_eq!(
+        "秋月无边",
+        fs::read_to_string(tree.join("sub/b.md")).unwrap()
+    );
+}
+
+#[test]
+fn directory_needs_output() {
+    let dir = tempdir().unwrap();
+
+    let output = Command::new(BIN)
+        .args(["-c", "s2t"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn file_dry_run() {
+    let dir = tempdir().unwrap();
+    let changed = dir.path().join("a.txt");
+    let unchanged = dir.path().join("b.txt");
+
+    fs::write(&changed, "凉风有讯").unwrap();
+    fs::write(&unchanged, "Hello").unwrap();
+
+    let output = Command::new(BIN)
+        .args(["-c", "s2twp", "-i", "--dry-run"])
+        .arg(&changed)
+        .arg(&unchanged)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!("{}\n", changed.display()),
+        String::from_utf8(output.stdout).unwrap()
+    );
+    assert_eq!("凉风有讯", fs::read_to_string(&changed).unwrap());
+}
+
+#[test]
+fn unsupported_flags() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    let tree = dir.path().join("docs");
+
+    fs::create_dir_all(&tree).unwrap();
+    fs::write(&file, "凉风有讯").unwrap();
+    fs::write(tree.join("b.md"), "秋月无边").unwrap();
+
+    let status = Command::new(BIN)
+        .args(["-c", "s2twp", "-i", "--backup", ".orig"])
+        .arg(&tree)
+        .status()
+        .unwrap();
+
+    assert!(!status.success());
+    assert_eq!("秋月无边", fs::read_to_string(tree.join("b.md")).unwrap());
+
+    for flags in [
+        &["--rename"][..],
+        &["--include", "*.txt"],
+        &["--exclude", "*.md"],
+    ] {
+        let status = Command::new(BIN)
+            .args(["-c", "s2twp", "-i"])
+            .args(flags)
+            .arg(&tree)
+            .arg(&file)
+            .status()
+            .unwrap();
+
+        assert!(!status.success());
+        assert_eq!("凉风有讯", fs::read_to_string(&file).unwrap());
+        assert_eq!("秋月无边", fs::read_to_string(tree.join("b.md")).unwrap());
+    }
+
+    let status = Command::new(BIN)
+        .args(["-c", "s2twp", "--dry-run"])
+        .arg(&file)
+        .status()
+        .unwrap();
+
+    assert!(!status.success());
+}
@@ -0,0 +1,56 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use opencc_rust_windows::{
+    Converter, DefaultConfig, MappingConverter, OpenCC, OpenCCError, PassthroughConverter,
+};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+/// Application code that only depends on the trait.
+fn title<C: Converter>(converter: &C, name: &str) -> Result<String, OpenCCError> {
+    let mut title = String::from("# ");
+
+    converter.convert_append(name, &mut title)?;
+
+    Ok(title)
+}
+
+#[test]
+fn backends() {
+    let mapping: MappingConverter = [("软件", "軟體"), ("软", "軟"), ("网络", "網路")]
+        .into_iter()
+        .collect();
+
+    assert_eq!("# 軟體和軟", title(&mapping, "软件和软").unwrap());
+    assert_eq!("# 软件", title(&PassthroughConverter, "软件").unwrap());
+
+    let opencc = OpenCC::new(get_config_path(DefaultConfig::S2TWP)).unwrap();
+
+    assert_eq!("# 這個軟體", title(&opencc, "这个软件").unwrap());
+
+    let converters: Vec<Box<dyn Converter>> = vec![Box::new(opencc), Box::new(mapping)];
+
+    for converter in &converters {
+        let mut output = Vec::new();
+
+        converter
+            .convert_stream(&mut "软件\n网络\n".as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!("軟體\n網路\n", String::from_utf8(output).unwrap());
+    }
+
+    assert!(matches!(
+        converters[1].convert_stream(&mut &b"\n\xff"[..], &mut Vec::new()),
+        Err(OpenCCError::InputInvalidUtf8(1))
+    ));
+    assert!(matches!(
+        converters[1].convert_cow("OpenCC"),
+        Ok(Cow::Borrowed("OpenCC"))
+    ));
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "derive")]
+
+use std::{collections::HashMap, path::PathBuf};
+
+use opencc_rust_windows::{Converter, Convertible, DefaultConfig, MappingConverter, OpenCC};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[derive(Debug, PartialEq, Convertible)]
+struct Item {
+    #[opencc(skip)]
+    id: String,
+    title: String,
+    price: u32,
+    description: Option<String>,
+    tags: Vec<Tag>,
+    attributes: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Convertible)]
+enum Tag {
+    Plain(String),
+    Code(#[opencc(skip)] String, String),
+    Empty,
+}
+
+#[derive(Debug, PartialEq, Convertible)]
+struct Wrapper<T>(T);
+
+#[test]
+fn derived() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let item = Item {
+        id: "软件".to_string(),
+        title: "这个软件".to_string(),
+        price: 42,
+        description: Some("网络".to_string()),
+        tags: vec![
+            Tag::Plain("软件".to_string()),
+            Tag::Code("软件".to_string(), "网络".to_string()),
+            Tag::Empty,
+        ],
+        attributes: HashMap::from([("软件".to_string(), "软件".to_string())]),
+    };
+
+    let item = Wrapper(Some(item)).converted(&opencc).unwrap();
+
+    assert_eq!(
+        Wrapper(Some(Item {
+            id: "软件".to_string(),
+            title: "這個軟體".to_string(),
+            price: 42,
+            description: Some("網路".to_string()),
+            tags: vec![
+                Tag::Plain("軟體".to_string()),
+                Tag::Code("软件".to_string(), "網路".to_string()),
+                Tag::Empty,
+            ],
+            attributes: HashMap::from([("软件".to_string(), "軟體".to_string())]),
+        })),
+        item
+    );
+}
+
+#[test]
+fn derived_with_converter() {
+    let converter = MappingConverter::new().with("软件", "軟體");
+
+    let tags = vec![
+        Tag::Plain("软件".to_string()),
+        Tag::Code("软件".to_string(), "软件".to_string()),
+    ];
+
+    assert_eq!(
+        vec![
+            Tag::Plain("軟體".to_string()),
+            Tag::Code("软件".to_string(), "軟體".to_string())
+        ],
+        Wrapper(tags)
+            .converted(&converter as &dyn Converter)
+            .unwrap()
+            .0
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "encoding")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, OpenCC, Repertoire, check_coverage};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn repertoires() {
+    assert!(Repertoire::Gb2312.contains('凉'));
+    assert!(!Repertoire::Gb2312.contains('涼'));
+    assert!(Repertoire::Gbk.contains('涼'));
+    assert!(Repertoire::Big5.contains('涼'));
+    assert!(!Repertoire::Big5.contains('凉'));
+    assert!(!Repertoire::Big5.contains('啱'));
+    assert!(Repertoire::Big5Hkscs.contains('啱'));
+    assert!(Repertoire::JisX0208.contains('涼'));
+    assert!(!Repertoire::JisX0208.contains('们'));
+}
+
+#[test]
+fn coverage_report() {
+    let report = check_coverage("涼風😀\n啱，a", Repertoire::Big5);
+
+    assert!(!report.is_covered());
+    assert_eq!(2, report.characters.len());
+
+    assert_eq!('😀', report.characters[0].character);
+    assert_eq!(6, report.characters[0].offset);
+    assert_eq!(
+        (1, 3),
+        (report.characters[0].line, report.characters[0].column)
+    );
+
+    assert_eq!('啱', report.characters[1].character);
+    assert_eq!(11, report.characters[1].offset);
+    assert_eq!(
+        (2, 1),
+        (report.characters[1].line, report.characters[1].column)
+    );
+
+    let report = check_coverage("涼風😀\n啱，a", Repertoire::Big5Hkscs);
+
+    assert_eq!(1, report.characters.len());
+    assert_eq!('😀', report.characters[0].character);
+}
+
+#[test]
+fn coverage_suggestions() {
+    let config_path = get_config_path(DefaultConfig::S2T);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let mut report = check_coverage("我们", Repertoire::Big5);
+    report.suggest(&[&opencc]).unwrap();
+
+    assert_eq!(1, report.characters.len());
+    assert_eq!(vec!['們'], report.characters[0].suggestions);
+
+    // 钟 has the candidates 鍾, 鐘 and 鈡, and only the first two are in Big5.
+    let mut report = check_coverage("鈡", Repertoire::Big5);
+    report.suggest(&[&opencc]).unwrap();
+
+    assert_eq!(vec!['鍾', '鐘'], report.characters[0].suggestions);
+
+    let mut report = check_coverage("檯", Repertoire::JisX0208);
+    report.suggest(&[&opencc]).unwrap();
+
+    assert_eq!(vec!['臺', '颱', '台'], report.characters[0].suggestions);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "csv")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{CsvColumn, CsvOptions, DefaultConfig, OpenCC, OpenCCError};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn selected_columns() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let options = CsvOptions {
+        columns: vec![
+            CsvColumn::Name("name".to_string()),
+            CsvColumn::Name("description".to_string()),
+        ],
+        ..CsvOptions::default()
+    };
+
+    let input = "\u{feff}id,name,code,description\r\n\
+                 软件1,软件,网络,\"这个软件,\r\n\"\"网络\"\"\"\r\n\
+                 2,\"网络\",软件,\n\
+                 3,软件";
+
+    let mut output = Vec::new();
+
+    opencc
+        .convert_csv(input.as_bytes(), &mut output, &options)
+        .unwrap();
+
+    assert_eq!(
+        "\u{feff}id,name,code,description\r\n\
+         软件1,軟體,网络,\"這個軟體,\r\n\"\"網路\"\"\"\r\n\
+         2,\"網路\",软件,\n\
+         3,軟體",
+        String::from_utf8(output).unwrap()
+    );
+
+    let options = CsvOptions {
+        has_headers: false,
+        columns: vec![CsvColumn::Index(1)],
+        ..CsvOptions::tsv()
+    };
+
+    let mut output = Vec::new();
+
+    opencc
+        .convert_csv("软件\t软件,网络\t软件\n".as_bytes(), &mut output, &options)
+        .unwrap();
+
+    assert_eq!(
+        "软件\t軟體,網路\t软件\n",
+        String::from_utf8(output).unwrap()
+    );
+
+    let options = CsvOptions {
+        columns: vec![CsvColumn::Name("title".to_string())],
+        ..CsvOptions::default()
+    };
+
+    assert!(matches!(
+        opencc.convert_csv("id,name\n".as_bytes(), Vec::new(), &options),
+        Err(OpenCCError::InvalidDocument { format: "CSV", .. })
+    ));
+}
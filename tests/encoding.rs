@@ -0,0 +1,180 @@
+#![cfg(feature = "encoding")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{
+    DefaultConfig, LegacyEncoding, OpenCC, OpenCCError, UnmappableCharacter, UnmappableFallback,
+};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn gb18030_to_big5() {
+    let config_path = get_config_path(DefaultConfig::S2TW);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    // 凉风有讯 in GB18030
+    let input = b"\xC1\xB9\xB7\xE7\xD3\xD0\xD1\xB6";
+
+    let output = opencc
+        .convert_encoded(
+            input,
+            LegacyEncoding::Gb18030,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::Error,
+        )
+        .unwrap();
+
+    // 涼風有訊 in Big5
+    assert_eq!(b"\xB2\x44\xAD\xB7\xA6\xB3\xB0\x54", output.bytes.as_slice());
+    assert!(output.unmappable.is_empty());
+}
+
+#[test]
+fn unmappable_fallback() {
+    let config_path = get_config_path(DefaultConfig::S2TW);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let output = opencc
+        .convert_encoded(
+            "凉😀风",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::NumericEntity,
+        )
+        .unwrap();
+
+    assert_eq!(b"\xB2\x44&#128512;\xAD\xB7", output.bytes.as_slice());
+    assert_eq!(
+        vec![UnmappableCharacter {
+            character: '😀',
+            offset: 3,
+        }],
+        output.unmappable
+    );
+
+    let output = opencc
+        .convert_encoded(
+            "凉😀风",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::Replacement("?".into()),
+        )
+        .unwrap();
+
+    assert_eq!(b"\xB2\x44?\xAD\xB7", output.bytes.as_slice());
+
+    assert!(matches!(
+        opencc.convert_encoded(
+            "凉😀风",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::Error,
+        ),
+        Err(OpenCCError::Unmappable {
+            character: '😀',
+            offset: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn big5_hkscs() {
+    let config_path = get_config_path(DefaultConfig::HK2T);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let big5 = opencc
+        .convert_encoded(
+            "啱",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::Replacement("?".into()),
+        )
+        .unwrap();
+
+    assert_eq!(b"?", big5.bytes.as_slice());
+
+    let hkscs = opencc
+        .convert_encoded(
+            "啱",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5Hkscs,
+            &UnmappableFallback::Error,
+        )
+        .unwrap();
+
+    assert_eq!(2, hkscs.bytes.len());
+
+    let decoded = opencc
+        .convert_encoded(
+            &hkscs.bytes,
+            LegacyEncoding::Big5Hkscs,
+            LegacyEncoding::Utf8,
+            &UnmappableFallback::Error,
+        )
+        .unwrap();
+
+    assert_eq!("啱".as_bytes(), decoded.bytes.as_slice());
+}
+
+#[test]
+fn big5_without_hkscs() {
+    let config_path = get_config_path(DefaultConfig::HK2T);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    // ① is in the ETEN extensions at C6A1, and 𠕇 is the HKSCS code FA40.
+    let big5 = opencc
+        .convert_encoded(
+            "a①b𠕇c",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::NumericEntity,
+        )
+        .unwrap();
+
+    assert_eq!(b"a&#9312;b&#132423;c", big5.bytes.as_slice());
+    assert_eq!(
+        vec![
+            UnmappableCharacter {
+                character: '①',
+                offset: 1,
+            },
+            UnmappableCharacter {
+                character: '𠕇',
+                offset: 5,
+            },
+        ],
+        big5.unmappable
+    );
+
+    assert!(matches!(
+        opencc.convert_encoded(
+            "①",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5,
+            &UnmappableFallback::Error,
+        ),
+        Err(OpenCCError::Unmappable {
+            character: '①',
+            offset: 0,
+            ..
+        })
+    ));
+
+    let hkscs = opencc
+        .convert_encoded(
+            "①𠕇",
+            LegacyEncoding::Utf8,
+            LegacyEncoding::Big5Hkscs,
+            &UnmappableFallback::Error,
+        )
+        .unwrap();
+
+    assert_eq!(b"\xC6\xA1\xFA\x40", hkscs.bytes.as_slice());
+}
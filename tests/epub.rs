@@ -0,0 +1,101 @@
+#![cfg(feature = "epub")]
+
+use std::{
+    io::{Cursor, Read, Write},
+    path::PathBuf,
+};
+
+use opencc_rust_windows::{DefaultConfig, HtmlOptions, OpenCC};
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+const OPF: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" xml:lang="zh-CN">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>软件设计</dc:title>
+    <dc:language>zh-CN</dc:language>
+  </metadata>
+  <manifest>
+    <item id="c1" href="软件.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+</package>
+"#;
+
+const CHAPTER: &str = r#"<html xmlns="http://www.w3.org/1999/xhtml" lang="zh-CN"><body><p>这个软件</p><code>软件</code></body></html>"#;
+
+const NCX: &str = r#"<ncx><docTitle><text>软件设计</text></docTitle></ncx>"#;
+
+#[test]
+fn epub() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, content) in [
+        ("OEBPS/content.opf", OPF.as_bytes()),
+        ("OEBPS/软件.xhtml", CHAPTER.as_bytes()),
+        ("OEBPS/toc.ncx", NCX.as_bytes()),
+        ("OEBPS/cover.png", b"\x89PNG\0\xff"),
+        ("mimetype", b"application/epub+zip"),
+    ] {
+        zip.start_file(name, deflated).unwrap();
+        zip.write_all(content).unwrap();
+    }
+
+    let input = zip.finish().unwrap();
+
+    let output = opencc
+        .convert_epub(
+            Cursor::new(input.into_inner()),
+            Cursor::new(Vec::new()),
+            &HtmlOptions::for_config(DefaultConfig::S2TWP),
+        )
+        .unwrap();
+
+    let mut archive = ZipArchive::new(Cursor::new(output.into_inner())).unwrap();
+
+    let read = |archive: &mut ZipArchive<_>, name: &str| {
+        let mut content = Vec::new();
+
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+
+        content
+    };
+
+    let mimetype = archive.by_index(0).unwrap();
+    assert_eq!("mimetype", mimetype.name());
+    assert_eq!(CompressionMethod::Stored, mimetype.compression());
+    drop(mimetype);
+
+    assert_eq!(5, archive.len());
+
+    assert_eq!(
+        OPF.replace("zh-CN", "zh-TW")
+            .replace("软件设计", "軟體設計"),
+        String::from_utf8(read(&mut archive, "OEBPS/content.opf")).unwrap()
+    );
+    assert_eq!(
+        r#"<html xmlns="http://www.w3.org/1999/xhtml" lang="zh-TW"><body><p>這個軟體</p><code>软件</code></body></html>"#,
+        String::from_utf8(read(&mut archive, "OEBPS/软件.xhtml")).unwrap()
+    );
+    assert_eq!(
+        r#"<ncx><docTitle><text>軟體設計</text></docTitle></ncx>"#,
+        String::from_utf8(read(&mut archive, "OEBPS/toc.ncx")).unwrap()
+    );
+    assert_eq!(
+        b"\x89PNG\0\xff".to_vec(),
+        read(&mut archive, "OEBPS/cover.png")
+    );
+}
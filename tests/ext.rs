@@ -0,0 +1,38 @@
+#![cfg(feature = "static-dictionaries")]
+
+use std::thread;
+
+use opencc_rust_windows::{ConvertExt, DefaultConfig};
+
+#[test]
+fn convert_ext() {
+    assert_eq!("這個軟體", "这个软件".to_tw_phrases());
+    assert_eq!("這個軟件", String::from("这个软件").to_traditional());
+    assert_eq!("这个软件", "這個軟體".to_cn_phrases());
+    assert_eq!(
+        "軟件",
+        "软件".try_convert_with(DefaultConfig::S2HK).unwrap()
+    );
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| thread::spawn(|| "网络".convert_with(DefaultConfig::S2TWP)))
+        .collect();
+
+    for handle in handles {
+        assert_eq!("網路", handle.join().unwrap());
+    }
+}
+
+#[test]
+fn display_adapter() {
+    let title = "软件";
+
+    assert_eq!(
+        "[  軟體]",
+        format!("[{:>4}]", title.display_with(DefaultConfig::S2TWP))
+    );
+    assert_eq!(
+        "软件: 軟件",
+        format!("{}: {}", title, title.display_with(DefaultConfig::S2HK))
+    );
+}
@@ -0,0 +1,93 @@
+use std::{fs, path::PathBuf};
+
+use opencc_rust_windows::{DefaultConfig, OpenCC, OpenCCError};
+use tempfile::tempdir;
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn convert_file() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src.txt");
+    let dst = dir.path().join("dst.txt");
+
+    fs::write(&src, "凉风有讯，\n秋月无边\0").unwrap();
+    fs::write(&dst, "old").unwrap();
+
+    opencc.convert_file(&src, &dst).unwrap();
+
+    assert_eq!("凉风有讯，\n秋月无边\0", fs::read_to_string(&src).unwrap());
+    assert_eq!("涼風有訊，\n秋月無邊\0", fs::read_to_string(&dst).unwrap());
+    assert_eq!(2, fs::read_dir(dir.path()).unwrap().count());
+}
+
+#[test]
+fn convert_file_in_place() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("file.txt");
+    let backup = dir.path().join("file.txt.bak");
+
+    fs::write(&path, "凉风有讯").unwrap();
+
+    opencc
+        .convert_file_in_place_with_backup(&path, &backup)
+        .unwrap();
+
+    assert_eq!("涼風有訊", fs::read_to_string(&path).unwrap());
+    assert_eq!("凉风有讯", fs::read_to_string(&backup).unwrap());
+
+    opencc.convert_file_in_place(&path).unwrap();
+
+    assert_eq!("涼風有訊", fs::read_to_string(&path).unwrap());
+}
+
+#[test]
+fn convert_file_invalid_utf8() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("file.txt");
+
+    fs::write(&path, b"\xE5\x87\x89\n\xFF").unwrap();
+
+    assert!(matches!(
+        opencc.convert_file_in_place(&path),
+        Err(OpenCCError::InputInvalidUtf8(4))
+    ));
+    assert_eq!(b"\xE5\x87\x89\n\xFF", fs::read(&path).unwrap().as_slice());
+    assert_eq!(1, fs::read_dir(dir.path()).unwrap().count());
+}
+
+#[cfg(unix)]
+#[test]
+fn convert_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("script.sh");
+
+    fs::write(&path, "echo 凉风有讯").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+    opencc.convert_file_in_place(&path).unwrap();
+
+    assert_eq!(
+        0o750,
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777
+    );
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "html")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, HtmlOptions, OpenCC};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn html_text_and_attributes() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+  <meta charset="utf-8">
+  <meta name="description" content="软件设计">
+  <meta content="软件" name="author">
+  <title>软件</title>
+  <style>p::before { content: "软件"; }</style>
+  <script>let s = "</p>软件";</script>
+</head>
+<body>
+  <!-- 软件 -->
+  <p data-x="软件" title='软件' >这个<b>软件</b> 1 < 2</p>
+  <input placeholder=软件>
+  <p>用 <code>软件<i>软件</i></code> 来<SPAN translate="no">软件 <span>网络</span></SPAN>设计</p>
+  <div lang="en"><img alt="图标" src="图标.png"/></div>
+</body>
+</html>
+"#;
+
+    let output = opencc
+        .convert_html(input, &HtmlOptions::for_config(DefaultConfig::S2TWP))
+        .unwrap();
+
+    assert_eq!(
+        r#"<!DOCTYPE html>
+<html lang="zh-TW">
+<head>
+  <meta charset="utf-8">
+  <meta name="description" content="軟體設計">
+  <meta content="软件" name="author">
+  <title>軟體</title>
+  <style>p::before { content: "软件"; }</style>
+  <script>let s = "</p>软件";</script>
+</head>
+<body>
+  <!-- 软件 -->
+  <p data-x="软件" title='軟體' >這個<b>軟體</b> 1 < 2</p>
+  <input placeholder=軟體>
+  <p>用 <code>软件<i>软件</i></code> 來<SPAN translate="no">软件 <span>网络</span></SPAN>設計</p>
+  <div lang="en"><img alt="圖標" src="图标.png"/></div>
+</body>
+</html>
+"#,
+        output
+    );
+}
+
+#[test]
+fn xml_text() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input =
+        "<?xml version=\"1.0\"?>\n<doc xml:lang=\"zh\"><code/><p>软件<![CDATA[<网络>]]></p></doc>";
+
+    let output = opencc
+        .convert_html(input, &HtmlOptions::for_config(DefaultConfig::S2TWP))
+        .unwrap();
+
+    assert_eq!(
+        "<?xml version=\"1.0\"?>\n<doc xml:lang=\"zh-TW\"><code/><p>軟體<![CDATA[<網路>]]></p></doc>",
+        output
+    );
+}
+
+#[test]
+fn target_language_tag() {
+    assert_eq!(Some("zh-TW"), DefaultConfig::S2TWP.target_language_tag());
+    assert_eq!(Some("zh-CN"), DefaultConfig::TW2SP.target_language_tag());
+    assert_eq!(None, DefaultConfig::T2JP.target_language_tag());
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "markdown")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, MarkdownOptions, OpenCC};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn markdown_prose_only() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "# 软件设计\n\n\
+                 这个*软件*用 `凉风` 来[转换](docs/转换.md \"标题\")。  \n\
+                 <span title=\"凉风\">凉风</span>\n\n\
+                 ![图标](图标.png \"图标\") <https://example.com/凉风>\n\n\
+                 ```rust\n\
+                 // 凉风有讯\n\
+                 ```\n\n\
+                 [ref]: docs/说明.md \"说明\"\n";
+
+    let output = opencc
+        .convert_markdown(input, &MarkdownOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "# 軟體設計\n\n\
+         這個*軟體*用 `凉风` 來[轉換](docs/转换.md \"标题\")。  \n\
+         <span title=\"凉风\">涼風</span>\n\n\
+         ![图标](图标.png \"图标\") <https://example.com/凉风>\n\n\
+         ```rust\n\
+         // 凉风有讯\n\
+         ```\n\n\
+         [ref]: docs/说明.md \"说明\"\n",
+        output
+    );
+
+    let options = MarkdownOptions {
+        alt_text: true,
+        titles: true,
+        ..MarkdownOptions::default()
+    };
+
+    let output = opencc.convert_markdown(input, &options).unwrap();
+
+    assert_eq!(
+        "# 軟體設計\n\n\
+         這個*軟體*用 `凉风` 來[轉換](docs/转换.md \"標題\")。  \n\
+         <span title=\"凉风\">涼風</span>\n\n\
+         ![圖標](图标.png \"圖標\") <https://example.com/凉风>\n\n\
+         ```rust\n\
+         // 凉风有讯\n\
+         ```\n\n\
+         [ref]: docs/说明.md \"說明\"\n",
+        output
+    );
+}
+
+#[test]
+fn markdown_front_matter() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "---\r\n\
+                 title: 软件设计\r\n\
+                 slug: 软件设计\r\n\
+                 tags:\r\n\
+                 \x20 - 网络\r\n\
+                 ---\r\n\
+                 软件\r\n";
+
+    let options = MarkdownOptions {
+        front_matter_keys: vec!["title".into(), "tags".into()],
+        ..MarkdownOptions::default()
+    };
+
+    let output = opencc.convert_markdown(input, &options).unwrap();
+
+    assert_eq!(
+        "---\r\n\
+         title: 軟體設計\r\n\
+         slug: 软件设计\r\n\
+         tags:\r\n\
+         \x20 - 網路\r\n\
+         ---\r\n\
+         軟體\r\n",
+        output
+    );
+
+    let input = "+++\ntitle = \"软件\"\n[extra]\ntitle = \"软件\"\n+++\n\n软件\n";
+
+    let output = opencc
+        .convert_markdown(input, &MarkdownOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "+++\ntitle = \"軟體\"\n[extra]\ntitle = \"软件\"\n+++\n\n軟體\n",
+        output
+    );
+}
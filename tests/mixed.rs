@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use opencc_rust_windows::{ConfigCache, DefaultConfig, OpenCCError};
+
+fn get_config_dir() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path
+}
+
+#[test]
+fn paragraphs_and_markers() {
+    let cache = ConfigCache::new(get_config_dir());
+
+    let mut configs = [Some(DefaultConfig::S2TWP), Some(DefaultConfig::S2HK), None].into_iter();
+
+    let output = cache
+        .convert_paragraphs("软件\n网络\r\n\r\n  \n软件\n\n软件", |_| {
+            configs.next().flatten()
+        })
+        .unwrap();
+
+    assert_eq!("軟體\n網路\r\n\r\n  \n軟件\n\n软件", output);
+    assert_eq!(2, cache.len());
+
+    let output = cache
+        .convert_marked(
+            "软件<!--opencc:s2hk-->软件<!--opencc:none-->软件<!--/opencc-->软件<!--/opencc-->软件",
+            Some(DefaultConfig::S2TWP),
+        )
+        .unwrap();
+
+    assert_eq!(
+        "軟體<!--opencc:s2hk-->軟件<!--opencc:none-->软件<!--/opencc-->軟件<!--/opencc-->軟體",
+        output
+    );
+
+    assert!(matches!(
+        cache.convert_marked("<!--opencc:s2xx-->软件", None),
+        Err(OpenCCError::UnknownConfig(_))
+    ));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[cfg(feature = "html")]
+#[test]
+fn html_by_language() {
+    use opencc_rust_windows::HtmlOptions;
+
+    let cache = ConfigCache::new(get_config_dir());
+
+    let input = r#"<div lang="zh-CN">软件<q lang="zh-HK" title="软件">软件<div>网络</div>软件</q>软件</div><p>软件</p>"#;
+
+    let output = cache
+        .convert_html_by_language(input, &HtmlOptions::default(), |lang| match lang {
+            Some(lang) if lang.eq_ignore_ascii_case("zh-HK") => Some(DefaultConfig::S2HK),
+            Some(_) => Some(DefaultConfig::S2TWP),
+            None => None,
+        })
+        .unwrap();
+
+    assert_eq!(
+        r#"<div lang="zh-TW">軟體<q lang="zh-HK" title="軟件">軟件<div>網絡</div>軟件</q>軟體</div><p>软件</p>"#,
+        output
+    );
+}
@@ -1,6 +1,6 @@
-use std::path::PathBuf;
+use std::{borrow::Cow, fs, path::PathBuf};
 
-use opencc_rust_windows::{DefaultConfig, OpenCC};
+use opencc_rust_windows::{DefaultConfig, OpenCC, OpenCCError, Utf8Mode};
 
 fn get_config_path(config: DefaultConfig) -> PathBuf {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -82,3 +82,87 @@ fn s2twp_to_buffer() {
         &s
     );
 }
+
+#[test]
+fn s2twp_cow() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    assert!(matches!(
+        opencc.convert_cow("Hello, world! 123").unwrap(),
+        Cow::Borrowed("Hello, world! 123")
+    ));
+    assert!(matches!(
+        opencc.convert_cow("涼風有訊").unwrap(),
+        Cow::Borrowed("涼風有訊")
+    ));
+
+    let converted = opencc.convert_cow("凉风有讯").unwrap();
+    assert!(matches!(converted, Cow::Owned(_)));
+    assert_eq!("涼風有訊", converted);
+
+    assert!(matches!(
+        opencc.convert_cow("a\0b"),
+        Err(OpenCCError::InputContainsNull)
+    ));
+}
+
+#[test]
+fn cow_without_dictionary_matches() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("s2t.json");
+
+    fs::write(dir.path().join("words.txt"), "软件\t軟體\n网\t網\n").unwrap();
+    fs::write(
+        &config_path,
+        r#"{
+  "name": "Two words",
+  "segmentation": {"type": "mmseg", "dict": {"type": "text", "file": "words.txt"}},
+  "conversion_chain": [{"dict": {"type": "text", "file": "words.txt"}}]
+}"#,
+    )
+    .unwrap();
+
+    let opencc = OpenCC::new(&config_path).unwrap();
+
+    // None of the characters starts a key, so the text is returned without being converted at all.
+    assert!(matches!(
+        opencc.convert_cow("凉风有讯").unwrap(),
+        Cow::Borrowed("凉风有讯")
+    ));
+    assert!(matches!(opencc.convert_cow("网络").unwrap(), Cow::Owned(_)));
+}
+
+#[test]
+fn s2twp_bytes() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    assert_eq!(
+        "涼風\0有訊\0\0".as_bytes(),
+        opencc
+            .convert_bytes("凉风\0有讯\0\0", Utf8Mode::Strict)
+            .unwrap()
+    );
+
+    let invalid = b"\xE5\x87\x89\xFF\0\xE9\xA3\x8E";
+
+    assert!(matches!(
+        opencc.convert_bytes(invalid, Utf8Mode::Strict),
+        Err(OpenCCError::InputInvalidUtf8(3))
+    ));
+    assert_eq!(
+        "涼\u{FFFD}\0風".as_bytes(),
+        opencc.convert_bytes(invalid, Utf8Mode::Lossy).unwrap()
+    );
+}
+
+#[test]
+fn parse_default_config() {
+    assert_eq!(DefaultConfig::S2TWP, "s2twp".parse().unwrap());
+    assert_eq!(DefaultConfig::S2TWP, "S2TWP.json".parse().unwrap());
+    assert!(matches!(
+        "s2x".parse::<DefaultConfig>(),
+        Err(OpenCCError::UnknownConfig(_))
+    ));
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use opencc_rust_windows::{Converter, DefaultConfig, Normalization, OpenCC, OpenCCError, Pipeline};
+use regex::Regex;
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn stages_and_timings() {
+    let spaces = Regex::new(r"[ \t]*([，。])[ \t]*").unwrap();
+
+    let pipeline = Pipeline::new()
+        .then_normalize(Normalization::UnixLineEndings)
+        .then_normalize(Normalization::TrimLineEnds)
+        .then(
+            "s2t",
+            OpenCC::new(get_config_path(DefaultConfig::S2T)).unwrap(),
+        )
+        .then(
+            "t2tw",
+            OpenCC::new(get_config_path(DefaultConfig::T2TW)).unwrap(),
+        )
+        .then_fn("punctuation", move |input, output| {
+            output.push_str(&spaces.replace_all(input, "$1"));
+
+            Ok(())
+        });
+
+    assert_eq!(5, pipeline.len());
+
+    for _ in 0..2 {
+        assert_eq!(
+            "這個軟件，網絡。\n\n設計",
+            pipeline.convert("这个软件 ， 网络 。  \r\n\r设计").unwrap()
+        );
+    }
+
+    let mut output = String::from("> ");
+
+    pipeline.convert_append("软件", &mut output).unwrap();
+    assert_eq!("> 軟件", output);
+
+    let timings = pipeline.timings();
+
+    assert_eq!(
+        vec![
+            "unix-line-endings",
+            "trim-line-ends",
+            "s2t",
+            "t2tw",
+            "punctuation"
+        ],
+        timings.iter().map(|t| t.name.as_str()).collect::<Vec<_>>()
+    );
+    assert!(timings.iter().all(|t| t.calls == 3));
+
+    pipeline.reset_timings();
+    assert!(pipeline.timings().iter().all(|t| t.calls == 0));
+}
+
+#[test]
+fn failing_stage() {
+    let pipeline = Pipeline::new()
+        .then_normalize(Normalization::HalfwidthAscii)
+        .then_fn("reject", |input, output| {
+            if input.contains('!') {
+                return Err(OpenCCError::ConversionFailed(input.to_string()));
+            }
+
+            output.push_str(input);
+
+            Ok(())
+        });
+
+    assert!(matches!(
+        pipeline.convert("ＯＫ！"),
+        Err(OpenCCError::ConversionFailed(s)) if s == "OK!"
+    ));
+    assert_eq!("OK", pipeline.convert("ＯＫ").unwrap());
+    assert_eq!("软件", Pipeline::new().convert("软件").unwrap());
+}
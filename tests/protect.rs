@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use opencc_rust_windows::{ConvertOptions, DefaultConfig, OpenCC};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn protected_spans() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let mut options = ConvertOptions::default();
+    options.protected_terms = vec!["网络".into()];
+
+    let input = "这个软件<!--nocc-->软件<!--/nocc-->网络，\
+                 来https://example.com/软件?q=网络。\
+                 说team@example.com.\n\
+                 %1$s个软件{0}软件%%s";
+
+    let output = opencc.convert_with_options(input, &options).unwrap();
+
+    assert_eq!(
+        "這個軟體<!--nocc-->软件<!--/nocc-->网络，\
+         來https://example.com/软件?q=网络。\
+         說team@example.com.\n\
+         %1$s個軟體{0}軟體%%s",
+        output
+    );
+
+    let mut options = ConvertOptions::default();
+    options.markers = Vec::new();
+    options.urls = false;
+    options.emails = false;
+    options.placeholders = false;
+
+    assert_eq!(
+        "<!--nocc-->軟體<!--/nocc-->",
+        opencc
+            .convert_with_options("<!--nocc-->软件<!--/nocc-->", &options)
+            .unwrap()
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn protected_patterns() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let mut options = ConvertOptions::default();
+    options.patterns = vec![regex::Regex::new("「[^」]*」").unwrap()];
+
+    assert_eq!(
+        "軟體「软件网络」網路",
+        opencc
+            .convert_with_options("软件「软件网络」网络", &options)
+            .unwrap()
+    );
+}
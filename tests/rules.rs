@@ -0,0 +1,107 @@
+#![cfg(feature = "rules")]
+
+use std::{fs, path::PathBuf};
+
+use opencc_rust_windows::{Converter, DefaultConfig, OpenCCError, Pipeline, RuleSet};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn rule_file() {
+    let rules = RuleSet::parse(
+        "# Quotation marks\n\
+         literal\t“\t「\n\
+         literal\t”\t」\n\
+         \n\
+         regex\t[ ]+([，。])\t$1\tprev=\\p{Han}|」\n\
+         regex\t(?<n>\\d+)\\s*%\t${n}％\t!next=\\d\n\
+         literal\t\\s\\s\t\\s\t!prev=\\s",
+    )
+    .unwrap();
+
+    assert_eq!(5, rules.len());
+    assert_eq!(
+        "「软件」，价格 10％ ，OK 。",
+        rules.convert("“软件” ，价格 10  % ，OK  。").unwrap()
+    );
+
+    assert!(matches!(
+        RuleSet::parse("literal\ta\tb\nregex\t(\tx"),
+        Err(OpenCCError::InvalidDocument { format: "rules", message }) if message.starts_with("line 2:")
+    ));
+    assert!(RuleSet::parse("literal\ta\tb\tabove=x").is_err());
+    assert!(RuleSet::parse("literal\ta").is_err());
+}
+
+#[test]
+fn custom_config() {
+    let dir = tempfile::tempdir().unwrap();
+
+    for entry in fs::read_dir(get_config_path(DefaultConfig::S2TWP).parent().unwrap()).unwrap() {
+        let entry = entry.unwrap();
+
+        fs::copy(entry.path(), dir.path().join(entry.file_name())).unwrap();
+    }
+
+    fs::write(
+        dir.path().join("before.rules"),
+        "literal\t“\t「\nliteral\t”\t」\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("after.rules"),
+        "regex\t[ ]+([，。])\t$1\tprev=\\p{Han}|」\n",
+    )
+    .unwrap();
+
+    let config_path = dir.path().join("s2twp-rules.json");
+
+    fs::write(
+        &config_path,
+        r#"{
+  "name": "Simplified Chinese to Traditional Chinese (Taiwan standard, with phrases and rules)",
+  "segmentation": {"type": "mmseg", "dict": {"type": "ocd2", "file": "STPhrases.ocd2"}},
+  "conversion_chain": [
+    {"rules": "before.rules"},
+    {"dict": {"type": "group", "dicts": [
+      {"type": "ocd2", "file": "STPhrases.ocd2"},
+      {"type": "ocd2", "file": "STCharacters.ocd2"}
+    ]}},
+    {"dict": {"type": "ocd2", "file": "TWPhrases.ocd2"}},
+    {"rules": "after.rules"},
+    {"dict": {"type": "ocd2", "file": "TWVariants.ocd2"}}
+  ]
+}"#,
+    )
+    .unwrap();
+
+    let pipeline = Pipeline::from_config(&config_path).unwrap();
+
+    assert_eq!(
+        vec![
+            "before.rules",
+            "s2twp-rules#1",
+            "after.rules",
+            "s2twp-rules#4"
+        ],
+        pipeline
+            .timings()
+            .into_iter()
+            .map(|t| t.name)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "「這個軟體」，網路。",
+        pipeline.convert("“这个软件” ，网络 。").unwrap()
+    );
+
+    let pipeline = Pipeline::from_config(get_config_path(DefaultConfig::S2TWP)).unwrap();
+
+    assert_eq!(1, pipeline.len());
+    assert_eq!("軟體", pipeline.convert("软件").unwrap());
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "serde")]
+
+use serde::Deserialize;
+
+#[test]
+fn deserialized() {
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        id: String,
+        #[serde(with = "opencc_rust_windows::serde::s2twp")]
+        items: Vec<(String, Option<String>)>,
+        #[serde(with = "opencc_rust_windows::serde::s2hk")]
+        note: Option<String>,
+    }
+
+    let payload: Payload = serde_json::from_str(
+        r#"{"id": "软件", "items": [["软件", "网络"], ["这个", null]], "note": "软件"}"#,
+    )
+    .unwrap();
+
+    assert_eq!("软件", payload.id);
+    assert_eq!(
+        vec![
+            ("軟體".to_string(), Some("網路".to_string())),
+            ("這個".to_string(), None)
+        ],
+        payload.items
+    );
+    assert_eq!(Some("軟件".to_string()), payload.note);
+}
+
+#[test]
+fn missing_option() {
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        #[serde(with = "opencc_rust_windows::serde::s2twp")]
+        note: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct DefaultPayload {
+        #[serde(default, with = "opencc_rust_windows::serde::s2twp")]
+        note: Option<String>,
+    }
+
+    assert_eq!(
+        Some("軟體".to_string()),
+        serde_json::from_str::<Payload>(r#"{"note": "软件"}"#)
+            .unwrap()
+            .note
+    );
+    assert!(serde_json::from_str::<Payload>("{}").is_err());
+    assert_eq!(
+        None,
+        serde_json::from_str::<DefaultPayload>("{}").unwrap().note
+    );
+}
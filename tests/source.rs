@@ -0,0 +1,114 @@
+#![cfg(feature = "source")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, OpenCC, SourceLanguage, SourceOptions};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn rust_and_c() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = r####"/// 软件 /* 网络 */
+fn 软件<'a>(c: char) -> &'a str {
+    let _ = ['"', '软'];
+    /* 软件 /* 网络 */ 软件 */
+    let _ = r#"软件 "\t" 网络"#;
+    println!("{}", "软件\t网络 \u{8f6f}件");
+    "这个软件"
+}
+"####;
+
+    let output = opencc
+        .convert_source(input, SourceLanguage::Rust, &SourceOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        r####"/// 軟體 /* 網路 */
+fn 软件<'a>(c: char) -> &'a str {
+    let _ = ['"', '软'];
+    /* 軟體 /* 網路 */ 軟體 */
+    let _ = r#"軟體 "\t" 網路"#;
+    println!("{}", "軟體\t網路 \u{8f6f}件");
+    "這個軟體"
+}
+"####,
+        output
+    );
+
+    let options = SourceOptions {
+        comments: false,
+        strings: true,
+    };
+
+    let input = "#include \"软件.h\"\n\
+                 // 软件\n\
+                 const char *s = u8\"软件\\\"网络\";\n\
+                 auto r = R\"x(软件)\"网络)x\";\n";
+
+    let output = opencc
+        .convert_source(input, SourceLanguage::Cpp, &options)
+        .unwrap();
+
+    assert_eq!(
+        "#include \"软件.h\"\n\
+         // 软件\n\
+         const char *s = u8\"軟體\\\"網路\";\n\
+         auto r = R\"x(軟體)\"網路)x\";\n",
+        output
+    );
+}
+
+#[test]
+fn python_javascript_and_go() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "# 软件\n\
+                 def f(软件):\n    \"\"\"这个软件\"\"\"\n    return f'{软件!r} 网络 {{软件}}' + rb'\\'软件'\n";
+
+    let output = opencc
+        .convert_source(input, SourceLanguage::Python, &SourceOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "# 軟體\n\
+         def f(软件):\n    \"\"\"這個軟體\"\"\"\n    return f'{软件!r} 網路 {{軟體}}' + rb'\\'軟體'\n",
+        output
+    );
+
+    let input = "const a = /\"软件/g.test('软件'); // 网络\n\
+                 const b = `软件 ${a ? \"网络\" : {软件: 1}[x]} 软件`;\n\
+                 const c = x / 2 / y; /* 软件 */\n";
+
+    let output = opencc
+        .convert_source(input, SourceLanguage::TypeScript, &SourceOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "const a = /\"软件/g.test('軟體'); // 網路\n\
+         const b = `軟體 ${a ? \"網路\" : {软件: 1}[x]} 軟體`;\n\
+         const c = x / 2 / y; /* 軟體 */\n",
+        output
+    );
+
+    let input = "s := `软件\\n` + \"网络\\n\" + string('软')\n";
+
+    let output = opencc
+        .convert_source(input, SourceLanguage::Go, &SourceOptions::default())
+        .unwrap();
+
+    assert_eq!("s := `軟體\\n` + \"網路\\n\" + string('软')\n", output);
+
+    assert_eq!(
+        Some(SourceLanguage::TypeScript),
+        SourceLanguage::from_extension("TSX")
+    );
+}
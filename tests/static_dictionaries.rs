@@ -39,3 +39,59 @@ fn generate_static_dictionary() {
 
     println!("Conversion Successful!");
 }
+
+#[test]
+fn from_default_config() {
+    let opencc = OpenCC::from_default_config(DefaultConfig::TW2SP).unwrap();
+
+    assert_eq!("无", &opencc.convert("無").unwrap());
+}
+
+#[test]
+fn generate_static_dictionary_keeps_files() {
+    let dir = tempdir().unwrap();
+    let output_path = dir.path();
+
+    let config_path = output_path.join(DefaultConfig::TW2SP.get_file_name());
+
+    fs::write(&config_path, "custom").unwrap();
+
+    opencc_rust_windows::generate_static_dictionary(output_path, DefaultConfig::TW2SP).unwrap();
+
+    assert_eq!("custom", fs::read_to_string(&config_path).unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn from_default_config_private_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    OpenCC::from_default_config(DefaultConfig::TW2SP).unwrap();
+
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    let uid = unsafe { libc::geteuid() };
+    let dir = std::env::temp_dir().join(format!(
+        "opencc-rust-windows-{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        uid
+    ));
+
+    assert_eq!(
+        0o700,
+        fs::metadata(&dir).unwrap().permissions().mode() & 0o777
+    );
+
+    // A truncated file in the private directory is written again.
+    let expected = tempdir().unwrap();
+
+    opencc_rust_windows::generate_static_dictionary(expected.path(), DefaultConfig::T2JP).unwrap();
+
+    let config = fs::read(expected.path().join(DefaultConfig::T2JP.get_file_name())).unwrap();
+    let config_path = dir.join(DefaultConfig::T2JP.get_file_name());
+
+    OpenCC::from_default_config(DefaultConfig::T2JP).unwrap();
+    fs::write(&config_path, &config[..config.len() / 2]).unwrap();
+    OpenCC::from_default_config(DefaultConfig::T2JP).unwrap();
+
+    assert_eq!(config, fs::read(&config_path).unwrap());
+}
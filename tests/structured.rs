@@ -0,0 +1,164 @@
+#![cfg(any(feature = "json", feature = "yaml", feature = "toml"))]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, OpenCC, StructuredOptions};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_values() {
+    use opencc_rust_windows::OpenCCError;
+
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = r#"{
+  "软件": "软件\n\"网络\"",
+  "escaped": "\u8f6f\u4ef6 1",
+  "items": [ {"title": "网络", "id": "网络"}, 1.5e3, true, null ],
+  "code": "软件"
+}"#;
+
+    let options = StructuredOptions {
+        exclude: vec!["/items/*/id".into(), "/code".into()],
+        ..StructuredOptions::default()
+    };
+
+    let output = opencc.convert_json(input, &options).unwrap();
+
+    assert_eq!(
+        r#"{
+  "软件": "軟體\n\"網路\"",
+  "escaped": "\u8edf\u9ad4 1",
+  "items": [ {"title": "網路", "id": "网络"}, 1.5e3, true, null ],
+  "code": "软件"
+}"#,
+        output
+    );
+
+    let options = StructuredOptions {
+        keys: true,
+        include: vec!["/软件".into()],
+        ..StructuredOptions::default()
+    };
+
+    let output = opencc.convert_json(input, &options).unwrap();
+
+    assert!(
+        output.starts_with("{\n  \"軟體\": \"軟體\\n\\\"網路\\\"\",\n  \"escaped\": \"\\u8f6f")
+    );
+
+    assert!(matches!(
+        opencc.convert_json("{\"a\": [1, }", &StructuredOptions::default()),
+        Err(OpenCCError::InvalidDocument { format: "JSON", .. })
+    ));
+
+    assert_eq!(
+        r#"["\u8edf\u9ad4\u0000\u7db2\u8def", "a\u0000b"]"#,
+        opencc
+            .convert_json(
+                r#"["\u8f6f\u4ef6\u0000\u7f51\u7edc", "a\u0000b"]"#,
+                &StructuredOptions::default()
+            )
+            .unwrap()
+    );
+
+    for number in ["0", "-0", "10", "1.5", "-0.5e-3", "2E+10"] {
+        assert_eq!(
+            number,
+            opencc
+                .convert_json(number, &StructuredOptions::default())
+                .unwrap()
+        );
+    }
+
+    for number in ["nan", "inf", "infinity", "01", "+1", "1.", ".5", "1e", "-"] {
+        assert!(
+            matches!(
+                opencc.convert_json(number, &StructuredOptions::default()),
+                Err(OpenCCError::InvalidDocument { format: "JSON", .. })
+            ),
+            "{}",
+            number
+        );
+    }
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_values() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "# 软件\n\
+                 软件: 软件 # 软件\n\
+                 list:\n\
+                 \x20 - '网络'\n\
+                 \x20 - {id: 网络, name: \"网络\"}\n\
+                 text: |\n\
+                 \x20 软件\n\
+                 \x20 网络\n";
+
+    let options = StructuredOptions {
+        keys: true,
+        exclude: vec!["/list/1/id".into()],
+        ..StructuredOptions::default()
+    };
+
+    let output = opencc.convert_yaml(input, &options).unwrap();
+
+    assert_eq!(
+        "# 软件\n\
+         軟體: 軟體 # 软件\n\
+         list:\n\
+         \x20 - '網路'\n\
+         \x20 - {id: 网络, name: \"網路\"}\n\
+         text: |\n\
+         \x20 軟體\n\
+         \x20 網路\n",
+        output
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_values() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "# 软件\n\
+                 \"软件\" = \"软件\"\n\
+                 \n\
+                 [\"网络\"]\n\
+                 list = ['网络', { \"软件\" = \"\"\"软件\"\"\" }]\n\
+                 \n\
+                 [[items]]\n\
+                 title = \"网络\" # 网络\n";
+
+    let options = StructuredOptions {
+        keys: true,
+        include: vec!["/软件".into(), "/网络".into()],
+        ..StructuredOptions::default()
+    };
+
+    let output = opencc.convert_toml(input, &options).unwrap();
+
+    assert_eq!(
+        "# 软件\n\
+         \"軟體\" = \"軟體\"\n\
+         \n\
+         [\"网络\"]\n\
+         list = ['網路', { \"軟體\" = \"\"\"軟體\"\"\" }]\n\
+         \n\
+         [[items]]\n\
+         title = \"网络\" # 网络\n",
+        output
+    );
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "subtitle")]
+
+use std::path::PathBuf;
+
+use opencc_rust_windows::{DefaultConfig, OpenCC, SubtitleFormat, SubtitleOptions};
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+#[test]
+fn srt_and_webvtt() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "1\r\n\
+                 00:00:01,000 --> 00:00:02,000\r\n\
+                 {\\an8}<i>软件</i> 网络\r\n\
+                 \r\n\
+                 2\r\n\
+                 00:00:03,000 --> 00:00:04,000\r\n\
+                 1 < 2 软件\r\n";
+
+    let output = opencc
+        .convert_subtitles(input, SubtitleFormat::Srt, &SubtitleOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "1\r\n\
+         00:00:01,000 --> 00:00:02,000\r\n\
+         {\\an8}<i>軟體</i> 網路\r\n\
+         \r\n\
+         2\r\n\
+         00:00:03,000 --> 00:00:04,000\r\n\
+         1 < 2 軟體\r\n",
+        output
+    );
+
+    let input = "WEBVTT 软件\n\
+                 \n\
+                 NOTE 软件\n\
+                 \n\
+                 软件\n\
+                 00:01.000 --> 00:02.000 align:start\n\
+                 <v 软件>网络 {软件}\n";
+
+    let output = opencc
+        .convert_subtitles(input, SubtitleFormat::WebVtt, &SubtitleOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "WEBVTT 软件\n\
+         \n\
+         NOTE 软件\n\
+         \n\
+         软件\n\
+         00:01.000 --> 00:02.000 align:start\n\
+         <v 软件>網路 {軟體}\n",
+        output
+    );
+}
+
+#[test]
+fn ass() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+    let opencc = OpenCC::new(config_path).unwrap();
+
+    let input = "[Script Info]\n\
+                 Title: 软件\n\
+                 \n\
+                 [V4+ Styles]\n\
+                 Format: Name, Fontname, Fontsize\n\
+                 Style: 软件,微软雅黑,20\n\
+                 \n\
+                 [Events]\n\
+                 Format: Layer, Start, End, Style, Text\n\
+                 Dialogue: 0,0:00:01.00,0:00:02.00,软件,{\\fn微软雅黑\\r软件}软件, 网络<i>\n\
+                 Comment: 0,0:00:01.00,0:00:02.00,软件,软件\n";
+
+    let output = opencc
+        .convert_subtitles(input, SubtitleFormat::Ass, &SubtitleOptions::default())
+        .unwrap();
+
+    assert_eq!(
+        "[Script Info]\n\
+         Title: 软件\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize\n\
+         Style: 软件,微软雅黑,20\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Text\n\
+         Dialogue: 0,0:00:01.00,0:00:02.00,软件,{\\fn微软雅黑\\r软件}軟體, 網路<i>\n\
+         Comment: 0,0:00:01.00,0:00:02.00,软件,软件\n",
+        output
+    );
+
+    let options = SubtitleOptions {
+        style_names: true,
+        font_names: true,
+    };
+
+    let output = opencc
+        .convert_subtitles(input, SubtitleFormat::Ass, &options)
+        .unwrap();
+
+    assert_eq!(
+        "[Script Info]\n\
+         Title: 软件\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize\n\
+         Style: 軟體,微軟雅黑,20\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Text\n\
+         Dialogue: 0,0:00:01.00,0:00:02.00,軟體,{\\fn微軟雅黑\\r軟體}軟體, 網路<i>\n\
+         Comment: 0,0:00:01.00,0:00:02.00,软件,软件\n",
+        output
+    );
+
+    assert_eq!(
+        Some(SubtitleFormat::Ass),
+        SubtitleFormat::from_extension("SSA")
+    );
+}
@@ -0,0 +1,343 @@
+#![cfg(feature = "tree")]
+
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use opencc_rust_windows::{
+    Converter, DefaultConfig, FileStatus, OpenCC, OpenCCError, TreeOptions, TreeOutput,
+    convert_tree,
+};
+use tempfile::tempdir;
+
+fn get_config_path(config: DefaultConfig) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("opencc");
+    path.push(config.get_file_name());
+    path
+}
+
+fn create_tree() -> tempfile::TempDir {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    fs::create_dir_all(root.join("docs/guide")).unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+
+    fs::write(root.join("docs/index.md"), "凉风有讯").unwrap();
+    fs::write(root.join("docs/guide/intro.md"), "秋月无边").unwrap();
+    fs::write(root.join("docs/guide/english.md"), "Hello").unwrap();
+    fs::write(root.join("docs/notes.txt"), "凉风有讯").unwrap();
+    fs::write(root.join("docs/image.md"), b"\x89PNG\0\0").unwrap();
+    fs::write(root.join("target/build.md"), "凉风有讯").unwrap();
+
+    dir
+}
+
+#[test]
+fn convert_tree_in_place() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+
+    let dir = create_tree();
+    let root = dir.path();
+
+    let options = TreeOptions {
+        include: vec!["**/*.md".into()],
+        exclude: vec!["target".into()],
+        threads: 2,
+        ..TreeOptions::default()
+    };
+
+    let summary = convert_tree(
+        root,
+        &options,
+        |_| Some(config_path.as_path()),
+        |path| OpenCC::new(path),
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec![
+            PathBuf::from("docs/guide/intro.md"),
+            PathBuf::from("docs/index.md")
+        ],
+        summary
+            .changed()
+            .map(|e| e.path.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "2 converted, 1 unchanged, 2 skipped, 0 failed",
+        summary.to_string()
+    );
+
+    assert_eq!(
+        "涼風有訊",
+        fs::read_to_string(root.join("docs/index.md")).unwrap()
+    );
+    assert_eq!(
+        "秋月無邊",
+        fs::read_to_string(root.join("docs/guide/intro.md")).unwrap()
+    );
+    assert_eq!(
+        "凉风有讯",
+        fs::read_to_string(root.join("docs/notes.txt")).unwrap()
+    );
+    assert_eq!(
+        "凉风有讯",
+        fs::read_to_string(root.join("target/build.md")).unwrap()
+    );
+}
+
+#[test]
+fn convert_tree_mirror_and_dry_run() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+
+    let dir = create_tree();
+    let root = dir.path().join("docs");
+    let output = dir.path().join("docs-tw");
+
+    let mut options = TreeOptions {
+        include: vec!["**/*.md".into()],
+        output: TreeOutput::Mirror(output.clone()),
+        dry_run: true,
+        ..TreeOptions::default()
+    };
+
+    let summary = convert_tree(
+        &root,
+        &options,
+        |path| (!path.starts_with("guide")).then_some(config_path.as_path()),
+        |path| OpenCC::new(path),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        summary
+            .entries
+            .iter()
+            .find(|e| e.path == Path::new("index.md"))
+            .unwrap()
+            .status,
+        FileStatus::WouldChange
+    ));
+    assert_eq!(1, summary.changed().count());
+    assert!(!output.exists());
+
+    options.dry_run = false;
+
+    let summary = convert_tree(
+        &root,
+        &options,
+        |path| (!path.starts_with("guide")).then_some(config_path.as_path()),
+        |path| OpenCC::new(path),
+    )
+    .unwrap();
+
+    assert_eq!(0, summary.failed().count());
+    assert_eq!(
+        "涼風有訊",
+        fs::read_to_string(output.join("index.md")).unwrap()
+    );
+    assert_eq!(
+        "秋月无边",
+        fs::read_to_string(output.join("guide/intro.md")).unwrap()
+    );
+    assert_eq!(
+        "凉风有讯",
+        fs::read_to_string(output.join("notes.txt")).unwrap()
+    );
+    assert_eq!(
+        "凉风有讯",
+        fs::read_to_string(root.join("index.md")).unwrap()
+    );
+}
+
+#[test]
+fn convert_tree_rename() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    fs::create_dir_all(root.join("软件")).unwrap();
+    fs::write(
+        root.join("index.md"),
+        "[安装](%E8%BD%AF%E4%BB%B6/%E5%AE%89%E8%A3%85.md#top) <a href=\"软件/\">x</a>\n\n[ref]: ./数据.md\n",
+    )
+    .unwrap();
+    fs::write(root.join("软件/安装.md"), "[返回](../index.md)").unwrap();
+    fs::write(root.join("数据.md"), "data").unwrap();
+    fs::write(root.join("数據.md"), "data").unwrap();
+    fs::write(root.join("图.png"), b"\x89PNG\0").unwrap();
+
+    let options = TreeOptions {
+        rename: true,
+        ..TreeOptions::default()
+    };
+
+    let summary = convert_tree(
+        root,
+        &options,
+        |_| Some(config_path.as_path()),
+        |path| OpenCC::new(path),
+    )
+    .unwrap();
+
+    assert_eq!(0, summary.failed().count());
+    assert_eq!(1, summary.collisions.len());
+    assert_eq!(Path::new("數據.md"), summary.collisions[0].target);
+    assert_eq!(
+        vec![PathBuf::from("数据.md"), PathBuf::from("数據.md")],
+        {
+            let mut sources = summary.collisions[0].sources.clone();
+            sources.sort();
+            sources
+        }
+    );
+
+    assert!(root.join("軟體/安裝.md").exists());
+    assert!(root.join("圖.png").exists());
+    assert!(root.join("数据.md").exists());
+    assert!(root.join("数據.md").exists());
+    assert!(!root.join("软件").exists());
+
+    assert_eq!(
+        "[安裝](%E8%BB%9F%E9%AB%94/%E5%AE%89%E8%A3%9D.md#top) <a href=\"軟體/\">x</a>\n\n[ref]: ./数据.md\n",
+        fs::read_to_string(root.join("index.md")).unwrap()
+    );
+    assert_eq!(
+        "[返回](../index.md)",
+        fs::read_to_string(root.join("軟體/安裝.md")).unwrap()
+    );
+}
+
+#[test]
+fn convert_tree_rename_dir_failure() {
+    let config_path = get_config_path(DefaultConfig::S2TWP);
+
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    fs::create_dir_all(root.join("软件")).unwrap();
+    fs::write(root.join("软件/说明.md"), "凉风有讯").unwrap();
+    fs::write(root.join("index.md"), "[说明](软件/说明.md)").unwrap();
+
+    let options = TreeOptions {
+        rename: true,
+        ..TreeOptions::default()
+    };
+
+    let opened = AtomicUsize::new(0);
+
+    // The renames are planned with the first converter, so a directory created while the files are converted
+    // takes the new name of "软件" after the plan was made.
+    let summary = convert_tree(
+        root,
+        &options,
+        |_| Some(config_path.as_path()),
+        |path| {
+            if opened.fetch_add(1, Ordering::SeqCst) > 0 {
+                fs::create_dir_all(root.join("軟體")).unwrap();
+                fs::write(root.join("軟體/keep.txt"), "keep").unwrap();
+            }
+
+            OpenCC::new(path)
+        },
+    )
+    .unwrap();
+
+    let failed: Vec<_> = summary.failed().map(|e| e.path.as_path()).collect();
+    assert_eq!(vec![Path::new("软件")], failed);
+    assert!(summary.renamed_dirs.is_empty());
+
+    let entry = summary
+        .entries
+        .iter()
+        .find(|e| e.path == Path::new("软件/说明.md"))
+        .unwrap();
+    assert_eq!(Some(Path::new("软件/說明.md")), entry.renamed_to.as_deref());
+
+    assert_eq!(
+        "涼風有訊",
+        fs::read_to_string(root.join("软件/說明.md")).unwrap()
+    );
+    assert!(root.join("軟體/keep.txt").exists());
+}
+
+/// A converter that waits in its first conversion until another conversion is running at the same time.
+struct Meeting<'a> {
+    running: &'a AtomicUsize,
+    met: &'a AtomicUsize,
+    first: AtomicUsize,
+}
+
+impl Converter for Meeting<'_> {
+    fn convert_cow<'a>(&self, input: &'a str) -> Result<Cow<'a, str>, OpenCCError> {
+        self.running.fetch_add(1, Ordering::SeqCst);
+
+        if self.first.fetch_add(1, Ordering::SeqCst) == 0 {
+            let start = Instant::now();
+
+            while self.running.load(Ordering::SeqCst) < 2
+                && start.elapsed() < Duration::from_secs(10)
+            {
+                thread::yield_now();
+            }
+
+            if self.running.load(Ordering::SeqCst) >= 2 {
+                self.met.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        self.running.fetch_sub(1, Ordering::SeqCst);
+
+        Ok(Cow::Owned(input.to_uppercase()))
+    }
+}
+
+#[test]
+fn convert_tree_converters_per_thread() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    fs::write(root.join("a.txt"), "a").unwrap();
+    fs::write(root.join("b.txt"), "b").unwrap();
+
+    let opened = AtomicUsize::new(0);
+    let running = AtomicUsize::new(0);
+    let met = AtomicUsize::new(0);
+
+    let options = TreeOptions {
+        threads: 2,
+        ..TreeOptions::default()
+    };
+
+    let summary = convert_tree(
+        root,
+        &options,
+        |_| Some(()),
+        |_| {
+            opened.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Meeting {
+                running: &running,
+                met: &met,
+                first: AtomicUsize::new(0),
+            })
+        },
+    )
+    .unwrap();
+
+    assert_eq!(2, summary.changed().count());
+    assert_eq!("B", fs::read_to_string(root.join("b.txt")).unwrap());
+
+    // Each thread opened its own converter, and the two conversions ran at the same time.
+    assert_eq!(2, opened.load(Ordering::SeqCst));
+    assert!(met.load(Ordering::SeqCst) > 0);
+}